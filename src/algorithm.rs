@@ -0,0 +1,306 @@
+use crate::asc1_dilithium::{
+    OID_DILITHIUM2, OID_DILITHIUM3, OID_DILITHIUM5, OID_MLDSA44, OID_MLDSA44_ED25519, OID_MLDSA65,
+    OID_MLDSA65_ED25519, OID_MLDSA87,
+};
+use crate::composite::{self, LABEL_MLDSA44_ED25519, LABEL_MLDSA65_ED25519};
+use crate::error::CryptoError;
+use crystals_dilithium::{dilithium2, dilithium3, dilithium5, ml_dsa_44, ml_dsa_65, ml_dsa_87};
+use der::asn1::ObjectIdentifier;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    Dilithium2,
+    Dilithium3,
+    Dilithium5,
+    Mldsa44,
+    Mldsa65,
+    Mldsa87,
+    Mldsa44Ed25519,
+    Mldsa65Ed25519,
+}
+
+/// Key pair, message, context (always empty when the parameter set has no context) and
+/// whether to sign hedged (always false when the parameter set only signs deterministically)
+pub(crate) type SignFn = fn(&[u8], &[u8], &[u8], bool) -> Option<Vec<u8>>;
+/// Public key, message, signature and context
+pub(crate) type VerifyFn = fn(&[u8], &[u8], &[u8], &[u8]) -> bool;
+
+/// Sizes, identifiers and primitives of one parameter set.
+///
+/// Key pair bytes are the public key followed by the secret key, as produced by
+/// `Keypair::to_bytes()`.
+pub struct AlgorithmSpec {
+    pub algorithm: Algorithm,
+    /// Canonical name, accepted by `--algorithm`
+    pub name: &'static str,
+    /// Additional names accepted by `--algorithm`
+    pub aliases: &'static [&'static str],
+    /// Dotted OID string used in `AlgorithmIdentifier`
+    pub oid: &'static str,
+    pub public_key_len: usize,
+    pub secret_key_len: usize,
+    pub signature_len: usize,
+    /// Whether signatures bind a FIPS 204 context string
+    pub context: bool,
+    /// Whether hedged (randomized) signing is available besides deterministic signing
+    pub hedged: bool,
+    /// ML-DSA component of a composite ML-DSA + Ed25519 parameset
+    pub composite: Option<Algorithm>,
+    pub(crate) generate: fn(&[u8; 32]) -> Vec<u8>,
+    pub(crate) public_key: fn(&[u8]) -> Vec<u8>,
+    pub(crate) sign: SignFn,
+    pub(crate) verify: VerifyFn,
+}
+
+impl AlgorithmSpec {
+    pub fn keypair_len(&self) -> usize {
+        self.public_key_len + self.secret_key_len
+    }
+}
+
+/// Longest context string FIPS 204 allows
+pub const MAX_CONTEXT_LEN: usize = 255;
+
+/// Parameter set from the original round 3 Dilithium submission
+macro_rules! dilithium {
+    ($algorithm:expr, $module:ident, $name:expr, $aliases:expr, $oid:expr) => {
+        AlgorithmSpec {
+            algorithm: $algorithm,
+            name: $name,
+            aliases: $aliases,
+            oid: $oid,
+            public_key_len: $module::PUBLICKEYBYTES,
+            secret_key_len: $module::SECRETKEYBYTES,
+            signature_len: $module::SIGNBYTES,
+            context: false,
+            hedged: false,
+            composite: None,
+            generate: |seed| $module::Keypair::generate(Some(seed)).to_bytes().to_vec(),
+            public_key: |keypair| $module::Keypair::from_bytes(keypair).public.bytes.to_vec(),
+            sign: |keypair, message, _, _| {
+                Some($module::Keypair::from_bytes(keypair).sign(message).to_vec())
+            },
+            verify: |public, message, signature, _| {
+                $module::PublicKey::from_bytes(public).verify(message, signature)
+            },
+        }
+    };
+}
+
+/// FIPS 204 parameter set
+macro_rules! ml_dsa {
+    ($algorithm:expr, $module:ident, $name:expr, $aliases:expr, $oid:expr) => {
+        AlgorithmSpec {
+            algorithm: $algorithm,
+            name: $name,
+            aliases: $aliases,
+            oid: $oid,
+            public_key_len: $module::PUBLICKEYBYTES,
+            secret_key_len: $module::SECRETKEYBYTES,
+            signature_len: $module::SIGNBYTES,
+            context: true,
+            hedged: true,
+            composite: None,
+            generate: |seed| $module::Keypair::generate(Some(seed)).to_bytes().to_vec(),
+            public_key: |keypair| $module::Keypair::from_bytes(keypair).public.bytes.to_vec(),
+            sign: |keypair, message, context, hedged| {
+                $module::Keypair::from_bytes(keypair)
+                    .sign(message, Some(context), hedged)
+                    .ok()
+                    .map(|signature| signature.to_vec())
+            },
+            verify: |public, message, signature, context| {
+                $module::PublicKey::from_bytes(public).verify(message, signature, Some(context))
+            },
+        }
+    };
+}
+
+/// Composite ML-DSA + Ed25519 parameter set, see [`composite`]. The seed only determines the
+/// ML-DSA component; the Ed25519 key is drawn separately.
+macro_rules! composite {
+    ($algorithm:expr, $component:expr, $module:ident, $name:expr, $oid:expr, $label:expr) => {
+        AlgorithmSpec {
+            algorithm: $algorithm,
+            name: $name,
+            aliases: &[],
+            oid: $oid,
+            public_key_len: $module::PUBLICKEYBYTES + composite::ED25519_KEY_LEN,
+            secret_key_len: $module::SECRETKEYBYTES + composite::ED25519_KEY_LEN,
+            signature_len: $module::SIGNBYTES + composite::ED25519_SIGNATURE_LEN,
+            context: true,
+            hedged: true,
+            composite: Some($component),
+            generate: |seed| {
+                composite::keypair(
+                    &$module::Keypair::generate(Some(seed)).to_bytes(),
+                    $module::PUBLICKEYBYTES,
+                    &composite::random_ed25519_key(),
+                )
+            },
+            public_key: |keypair| {
+                keypair[..$module::PUBLICKEYBYTES + composite::ED25519_KEY_LEN].to_vec()
+            },
+            sign: |keypair, message, context, hedged| {
+                let (mldsa_keypair, ed25519_key) =
+                    composite::split_keypair(keypair, $module::PUBLICKEYBYTES);
+                let message = composite::message_representative($label, context, message);
+                let mldsa = $module::Keypair::from_bytes(&mldsa_keypair)
+                    .sign(&message, Some($label), hedged)
+                    .ok()?;
+                let ed25519 = composite::ed25519_sign(ed25519_key, &message);
+                Some([&mldsa[..], &ed25519[..]].concat())
+            },
+            verify: |public, message, signature, context| {
+                let (mldsa_public, ed25519_public) = public.split_at($module::PUBLICKEYBYTES);
+                let (mldsa, ed25519) = signature.split_at($module::SIGNBYTES);
+                let message = composite::message_representative($label, context, message);
+                $module::PublicKey::from_bytes(mldsa_public).verify(&message, mldsa, Some($label))
+                    && composite::ed25519_verify(ed25519_public, &message, ed25519)
+            },
+        }
+    };
+}
+
+/// Every supported parameter set. Adding one means adding an `Algorithm` variant and an entry here.
+pub static ALGORITHMS: [AlgorithmSpec; 8] = [
+    dilithium!(
+        Algorithm::Dilithium2,
+        dilithium2,
+        "dilithium2",
+        &["dil2"],
+        OID_DILITHIUM2
+    ),
+    dilithium!(
+        Algorithm::Dilithium3,
+        dilithium3,
+        "dilithium3",
+        &["dil3"],
+        OID_DILITHIUM3
+    ),
+    dilithium!(
+        Algorithm::Dilithium5,
+        dilithium5,
+        "dilithium5",
+        &["dil5"],
+        OID_DILITHIUM5
+    ),
+    ml_dsa!(Algorithm::Mldsa44, ml_dsa_44, "mldsa44", &[], OID_MLDSA44),
+    ml_dsa!(Algorithm::Mldsa65, ml_dsa_65, "mldsa65", &[], OID_MLDSA65),
+    ml_dsa!(Algorithm::Mldsa87, ml_dsa_87, "mldsa87", &[], OID_MLDSA87),
+    composite!(
+        Algorithm::Mldsa44Ed25519,
+        Algorithm::Mldsa44,
+        ml_dsa_44,
+        "mldsa44-ed25519",
+        OID_MLDSA44_ED25519,
+        LABEL_MLDSA44_ED25519
+    ),
+    composite!(
+        Algorithm::Mldsa65Ed25519,
+        Algorithm::Mldsa65,
+        ml_dsa_65,
+        "mldsa65-ed25519",
+        OID_MLDSA65_ED25519,
+        LABEL_MLDSA65_ED25519
+    ),
+];
+
+impl Algorithm {
+    pub fn spec(&self) -> &'static AlgorithmSpec {
+        ALGORITHMS
+            .iter()
+            .find(|spec| spec.algorithm == *self)
+            .expect("every algorithm has an entry in ALGORITHMS")
+    }
+
+    /// Dotted OID string used in `AlgorithmIdentifier`
+    pub fn oid_str(&self) -> &'static str {
+        self.spec().oid
+    }
+
+    pub fn oid(&self) -> ObjectIdentifier {
+        ObjectIdentifier::new_unwrap(self.oid_str())
+    }
+
+    pub fn from_oid(oid: &ObjectIdentifier) -> Result<Self, CryptoError> {
+        let oid = oid.to_string();
+        ALGORITHMS
+            .iter()
+            .find(|spec| spec.oid == oid)
+            .map(|spec| spec.algorithm)
+            .ok_or(CryptoError::UnknownAlgorithmOid(oid))
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.spec().name
+    }
+
+    /// Reject a context the parameter set cannot bind: any non-empty context for legacy
+    /// Dilithium, or one longer than [`MAX_CONTEXT_LEN`]
+    pub fn check_context(&self, context: &[u8]) -> Result<(), CryptoError> {
+        if !context.is_empty() && !self.spec().context {
+            return Err(CryptoError::InvalidContext(format!(
+                "{} does not support context strings",
+                self
+            )));
+        }
+        if context.len() > MAX_CONTEXT_LEN {
+            return Err(CryptoError::InvalidContext(format!(
+                "context is {} bytes, at most {} are allowed",
+                context.len(),
+                MAX_CONTEXT_LEN
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = CryptoError;
+
+    fn from_str(s: &str) -> Result<Self, CryptoError> {
+        let name = s.to_ascii_lowercase();
+        ALGORITHMS
+            .iter()
+            .find(|spec| spec.name == name || spec.aliases.contains(&name.as_str()))
+            .map(|spec| spec.algorithm)
+            .ok_or_else(|| CryptoError::InvalidAlgorithm(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_and_oids_resolve_to_their_entry() {
+        for spec in ALGORITHMS.iter() {
+            assert_eq!(spec.name.parse::<Algorithm>().unwrap(), spec.algorithm);
+            for alias in spec.aliases {
+                assert_eq!(alias.parse::<Algorithm>().unwrap(), spec.algorithm);
+            }
+            assert_eq!(
+                Algorithm::from_oid(&spec.algorithm.oid()).unwrap(),
+                spec.algorithm
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_oid_is_rejected() {
+        let oid = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.1");
+        assert!(matches!(
+            Algorithm::from_oid(&oid),
+            Err(CryptoError::UnknownAlgorithmOid(_))
+        ));
+    }
+}
@@ -0,0 +1,69 @@
+//! RFC 7468 PEM armor for labels that contain hyphens, such as `ML-DSA SIGNATURE`.
+//!
+//! `der::pem` rejects hyphens in labels although the RFC grammar allows them, so the
+//! structures introduced by this crate are armored here.
+use crate::error::CryptoError;
+use base64::{engine::general_purpose, Engine as _};
+use der::pem;
+
+/// Base64 characters per line, as in `der::pem`
+const LINE_WIDTH: usize = 64;
+
+pub fn encode(label: &str, der: &[u8]) -> String {
+    let base64 = general_purpose::STANDARD.encode(der);
+    let mut out = format!("-----BEGIN {}-----\n", label);
+    for line in base64.as_bytes().chunks(LINE_WIDTH) {
+        out.push_str(std::str::from_utf8(line).expect("base64 is ASCII"));
+        out.push('\n');
+    }
+    out.push_str(&format!("-----END {}-----\n", label));
+    out
+}
+
+/// Label and contents of the first PEM block in `bytes`
+pub fn decode(bytes: &[u8]) -> Result<(String, Vec<u8>), CryptoError> {
+    let error = |err: pem::Error| CryptoError::PemDecode(err.into());
+    let text = std::str::from_utf8(bytes).map_err(|_| error(pem::Error::CharacterEncoding))?;
+    let mut lines = text.trim_start().lines();
+    let label = lines
+        .next()
+        .and_then(|line| line.trim_end().strip_prefix("-----BEGIN "))
+        .and_then(|line| line.strip_suffix("-----"))
+        .ok_or_else(|| error(pem::Error::PreEncapsulationBoundary))?;
+    let end = format!("-----END {}-----", label);
+
+    let mut base64 = String::new();
+    for line in lines.by_ref() {
+        let line = line.trim();
+        if line == end {
+            let der = general_purpose::STANDARD
+                .decode(&base64)
+                .map_err(|_| error(pem::Error::EncapsulatedText))?;
+            return Ok((label.to_string(), der));
+        }
+        if line.contains(':') {
+            return Err(error(pem::Error::HeaderDisallowed));
+        }
+        base64.push_str(line);
+    }
+    Err(error(pem::Error::PostEncapsulationBoundary))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn armor_round_trip() {
+        let der = vec![0x30; 100];
+        let pem = encode("ML-DSA SIGNATURE", &der);
+        assert!(pem
+            .lines()
+            .all(|line| line.len() <= LINE_WIDTH || line.starts_with("-----")));
+        assert_eq!(
+            decode(pem.as_bytes()).unwrap(),
+            ("ML-DSA SIGNATURE".to_string(), der)
+        );
+        assert!(decode(pem.replace("-----END ML-DSA", "-----END OTHER").as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,232 @@
+use der::{
+    asn1::{
+        Any, AnyRef, BitString, BitStringRef, GeneralizedTime, ObjectIdentifier, OctetString,
+        OctetStringRef, SetOfVec, UintRef,
+    },
+    pem::PemLabel,
+    Choice, Sequence, ValueOrd,
+};
+
+pub const OID_DILITHIUM2: &str = "1.3.6.1.4.1.2.267.7.4.4";
+pub const OID_DILITHIUM3: &str = "1.3.6.1.4.1.2.267.7.6.5";
+pub const OID_DILITHIUM5: &str = "1.3.6.1.4.1.2.267.7.8.7";
+pub const OID_MLDSA44: &str = "2.16.840.1.101.3.4.3.17";
+pub const OID_MLDSA65: &str = "2.16.840.1.101.3.4.3.18";
+pub const OID_MLDSA87: &str = "2.16.840.1.101.3.4.3.19";
+
+/// Composite ML-DSA + Ed25519 with SHA-512 pre-hash (draft-ietf-lamps-pq-composite-sigs).
+/// The draft pairs ML-DSA-87 with Ed448, so there is no ML-DSA-87 + Ed25519 OID.
+pub const OID_MLDSA44_ED25519: &str = "1.3.6.1.5.5.7.6.39";
+pub const OID_MLDSA65_ED25519: &str = "1.3.6.1.5.5.7.6.48";
+
+/// HashML-DSA with SHA-512 pre-hash (FIPS 204 section 5.4). Not supported for signing: the
+/// dilithium backend only exposes pure ML-DSA, which cannot produce the pre-hash domain separator.
+pub const OID_HASH_MLDSA44_SHA512: &str = "2.16.840.1.101.3.4.3.32";
+pub const OID_HASH_MLDSA65_SHA512: &str = "2.16.840.1.101.3.4.3.33";
+pub const OID_HASH_MLDSA87_SHA512: &str = "2.16.840.1.101.3.4.3.34";
+
+/// Pre-hash function OIDs that HashML-DSA binds into the signed message
+pub const OID_SHA256: &str = "2.16.840.1.101.3.4.2.1";
+pub const OID_SHA512: &str = "2.16.840.1.101.3.4.2.3";
+pub const OID_SHAKE128: &str = "2.16.840.1.101.3.4.2.11";
+
+/// X.509 `AlgorithmIdentifier`
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Sequence, ValueOrd)]
+pub struct AlgorithmIdentifier {
+    pub algorithm: ObjectIdentifier,
+}
+
+/// X.509 `SubjectPublicKeyInfo` (SPKI)
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Sequence, ValueOrd)]
+pub struct SubjectPublicKeyInfoBorrowed<'a> {
+    pub algorithm: AlgorithmIdentifier,
+    #[asn1(type = "BIT STRING")]
+    pub subject_public_key: &'a [u8],
+}
+
+impl PemLabel for SubjectPublicKeyInfoBorrowed<'_> {
+    const PEM_LABEL: &'static str = "PUBLIC KEY";
+}
+
+/// X.509 `SubjectPublicKeyInfo` (SPKI)
+#[derive(Clone, Debug, Eq, PartialEq, Sequence, ValueOrd)]
+pub struct SubjectPublicKeyInfoOwned {
+    pub algorithm: AlgorithmIdentifier,
+    pub subject_public_key: BitString,
+}
+
+impl PemLabel for SubjectPublicKeyInfoOwned {
+    const PEM_LABEL: &'static str = "PUBLIC KEY";
+}
+
+/// `OneAsymmetricKey` (RFC 5958). `version` is 0 (v1), or 1 (v2) when `public_key` is present
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub struct OneAsymmetricKeyBorrowed<'a> {
+    pub version: u8,
+    pub private_key_algorithm: AlgorithmIdentifier,
+    #[asn1(type = "OCTET STRING")]
+    pub private_key: &'a [u8],
+    #[asn1(context_specific = "0", tag_mode = "IMPLICIT", optional = "true")]
+    pub attributes: Option<SetOfVec<AnyRef<'a>>>,
+    #[asn1(context_specific = "1", tag_mode = "IMPLICIT", optional = "true")]
+    pub public_key: Option<BitStringRef<'a>>,
+}
+
+impl PemLabel for OneAsymmetricKeyBorrowed<'_> {
+    const PEM_LABEL: &'static str = "PRIVATE KEY";
+}
+
+/// `OneAsymmetricKey` (RFC 5958). `version` is 0 (v1), or 1 (v2) when `public_key` is present
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub struct OneAsymmetricKeyOwned {
+    pub version: u8,
+    pub private_key_algorithm: AlgorithmIdentifier,
+    pub private_key: OctetString,
+    #[asn1(context_specific = "0", tag_mode = "IMPLICIT", optional = "true")]
+    pub attributes: Option<SetOfVec<Any>>,
+    #[asn1(context_specific = "1", tag_mode = "IMPLICIT", optional = "true")]
+    pub public_key: Option<BitString>,
+}
+
+impl PemLabel for OneAsymmetricKeyOwned {
+    const PEM_LABEL: &'static str = "PRIVATE KEY";
+}
+
+/// ML-DSA private key (RFC 9881), carried inside the `privateKey` OCTET STRING
+/// of `OneAsymmetricKey`:
+///
+/// ```text
+/// ML-DSA-PrivateKey ::= CHOICE {
+///   seed [0] OCTET STRING (SIZE (32)),
+///   expandedKey OCTET STRING,
+///   both SEQUENCE {
+///     seed OCTET STRING (SIZE (32)),
+///     expandedKey OCTET STRING } }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Choice)]
+pub enum MlDsaPrivateKey<'a> {
+    #[asn1(context_specific = "0", tag_mode = "IMPLICIT")]
+    Seed(OctetStringRef<'a>),
+    ExpandedKey(OctetStringRef<'a>),
+    Both(MlDsaPrivateKeyBoth<'a>),
+}
+
+/// `both` alternative of [`MlDsaPrivateKey`]
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub struct MlDsaPrivateKeyBoth<'a> {
+    pub seed: OctetStringRef<'a>,
+    pub expanded_key: OctetStringRef<'a>,
+}
+
+/// Self-describing signature container written by `sign --outform DER|PEM`:
+///
+/// ```text
+/// SignatureEnvelope ::= SEQUENCE {
+///   version        INTEGER (0),
+///   algorithm      AlgorithmIdentifier,
+///   keyFingerprint OCTET STRING (SIZE (32)), -- SHA-256 of the signer's SubjectPublicKeyInfo
+///   context        [0] IMPLICIT OCTET STRING OPTIONAL,
+///   preHash        [1] IMPLICIT OBJECT IDENTIFIER OPTIONAL, -- HashML-DSA hash, absent for pure
+///   signingTime    [2] IMPLICIT GeneralizedTime OPTIONAL,
+///   signature      BIT STRING }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub struct SignatureEnvelopeBorrowed<'a> {
+    pub version: u8,
+    pub algorithm: AlgorithmIdentifier,
+    pub key_fingerprint: OctetStringRef<'a>,
+    #[asn1(context_specific = "0", tag_mode = "IMPLICIT", optional = "true")]
+    pub context: Option<OctetStringRef<'a>>,
+    #[asn1(context_specific = "1", tag_mode = "IMPLICIT", optional = "true")]
+    pub pre_hash: Option<ObjectIdentifier>,
+    #[asn1(context_specific = "2", tag_mode = "IMPLICIT", optional = "true")]
+    pub signing_time: Option<GeneralizedTime>,
+    #[asn1(type = "BIT STRING")]
+    pub signature: &'a [u8],
+}
+
+impl PemLabel for SignatureEnvelopeBorrowed<'_> {
+    const PEM_LABEL: &'static str = "ML-DSA SIGNATURE";
+}
+
+pub const OID_CMS_DATA: &str = "1.2.840.113549.1.7.1";
+pub const OID_CMS_SIGNED_DATA: &str = "1.2.840.113549.1.7.2";
+pub const OID_ATTR_CONTENT_TYPE: &str = "1.2.840.113549.1.9.3";
+pub const OID_ATTR_MESSAGE_DIGEST: &str = "1.2.840.113549.1.9.4";
+pub const OID_ATTR_SIGNING_TIME: &str = "1.2.840.113549.1.9.5";
+pub const OID_SHA384: &str = "2.16.840.1.101.3.4.2.2";
+
+/// CMS `ContentInfo` (RFC 5652 section 3)
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub struct ContentInfo<'a> {
+    pub content_type: ObjectIdentifier,
+    #[asn1(context_specific = "0", tag_mode = "EXPLICIT")]
+    pub content: AnyRef<'a>,
+}
+
+impl PemLabel for ContentInfo<'_> {
+    const PEM_LABEL: &'static str = "CMS";
+}
+
+/// CMS `SignedData` (RFC 5652 section 5.1)
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub struct SignedData<'a> {
+    pub version: u8,
+    pub digest_algorithms: SetOfVec<DigestAlgorithmIdentifier<'a>>,
+    pub encap_content_info: EncapsulatedContentInfo<'a>,
+    #[asn1(context_specific = "0", tag_mode = "IMPLICIT", optional = "true")]
+    pub certificates: Option<SetOfVec<AnyRef<'a>>>,
+    #[asn1(context_specific = "1", tag_mode = "IMPLICIT", optional = "true")]
+    pub crls: Option<SetOfVec<AnyRef<'a>>>,
+    pub signer_infos: SetOfVec<SignerInfo<'a>>,
+}
+
+/// `eContent` is absent for a detached signature
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub struct EncapsulatedContentInfo<'a> {
+    pub e_content_type: ObjectIdentifier,
+    #[asn1(context_specific = "0", tag_mode = "EXPLICIT", optional = "true")]
+    pub e_content: Option<OctetStringRef<'a>>,
+}
+
+/// CMS `SignerInfo` (RFC 5652 section 5.3)
+#[derive(Clone, Debug, Eq, PartialEq, Sequence, ValueOrd)]
+pub struct SignerInfo<'a> {
+    pub version: u8,
+    pub sid: SignerIdentifier<'a>,
+    pub digest_algorithm: DigestAlgorithmIdentifier<'a>,
+    #[asn1(context_specific = "0", tag_mode = "IMPLICIT", optional = "true")]
+    pub signed_attrs: Option<SetOfVec<Attribute<'a>>>,
+    pub signature_algorithm: AlgorithmIdentifier,
+    pub signature: OctetStringRef<'a>,
+    #[asn1(context_specific = "1", tag_mode = "IMPLICIT", optional = "true")]
+    pub unsigned_attrs: Option<SetOfVec<Attribute<'a>>>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Choice, ValueOrd)]
+pub enum SignerIdentifier<'a> {
+    IssuerAndSerialNumber(IssuerAndSerialNumber<'a>),
+    #[asn1(context_specific = "0", tag_mode = "IMPLICIT")]
+    SubjectKeyIdentifier(OctetStringRef<'a>),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Sequence, ValueOrd)]
+pub struct IssuerAndSerialNumber<'a> {
+    pub issuer: AnyRef<'a>,
+    pub serial_number: UintRef<'a>,
+}
+
+/// Digest `AlgorithmIdentifier`. RFC 5754 omits the parameters of the SHA-2 family, but
+/// requires accepting an explicit NULL
+#[derive(Clone, Debug, Eq, PartialEq, Sequence, ValueOrd)]
+pub struct DigestAlgorithmIdentifier<'a> {
+    pub algorithm: ObjectIdentifier,
+    pub parameters: Option<AnyRef<'a>>,
+}
+
+/// X.501 `Attribute`, as used for CMS signed attributes
+#[derive(Clone, Debug, Eq, PartialEq, Sequence, ValueOrd)]
+pub struct Attribute<'a> {
+    pub attr_type: ObjectIdentifier,
+    pub attr_values: SetOfVec<AnyRef<'a>>,
+}
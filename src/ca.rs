@@ -0,0 +1,723 @@
+//! Offline certificate authority kept in a directory, so that it works on an air-gapped host:
+//!
+//! ```text
+//! ca.key     CA private key, stored as it was given to `create`
+//! ca.crt     CA certificate, PEM
+//! serial     next serial number in hex
+//! index.txt  issued certificates, in the `openssl ca` index format
+//! crlnumber  cRLNumber of the next CRL in hex
+//! certs/     issued certificates, PEM, named by serial number
+//! ```
+//!
+//! The private key is only ever read by the caller, which may keep it encrypted.
+use crate::error::CryptoError;
+use crate::keys::SecretKey;
+use crate::x509::{
+    self, CertReq, Certificate, CertificateList, CertificateOptions, CrlOptions, ExtensionOptions,
+    RevokedCertificate,
+};
+use der::oid::AssociatedOid;
+use der::{DateTime, Decode};
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+use x509_cert::ext::pkix::crl::CrlReason;
+
+pub use crate::x509::reason_name;
+use x509_cert::ext::pkix::{KeyUsages, SubjectAltName};
+
+pub const KEY_FILE: &str = "ca.key";
+pub const CERTIFICATE_FILE: &str = "ca.crt";
+pub const SERIAL_FILE: &str = "serial";
+pub const INDEX_FILE: &str = "index.txt";
+pub const CERTS_DIR: &str = "certs";
+pub const CRL_NUMBER_FILE: &str = "crlnumber";
+
+/// Revocation reasons `revoke` accepts, by their RFC 5280 names
+const REVOCATION_REASONS: [(&str, CrlReason); 6] = [
+    ("unspecified", CrlReason::Unspecified),
+    ("keyCompromise", CrlReason::KeyCompromise),
+    ("CACompromise", CrlReason::CaCompromise),
+    ("affiliationChanged", CrlReason::AffiliationChanged),
+    ("superseded", CrlReason::Superseded),
+    ("cessationOfOperation", CrlReason::CessationOfOperation),
+];
+
+/// Extensions an issued certificate gets; subject and subjectAltName come from the request
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Profile {
+    /// TLS server: digitalSignature, serverAuth
+    Server,
+    /// TLS client: digitalSignature, clientAuth
+    Client,
+    /// Code signing: digitalSignature, codeSigning
+    CodeSigning,
+    /// CA below this one that can only issue end-entity certificates (pathLen 0)
+    IntermediateCa,
+}
+
+impl Profile {
+    pub fn extensions(self) -> ExtensionOptions {
+        let purpose = match self {
+            Profile::Server => x509::ID_KP_SERVER_AUTH,
+            Profile::Client => x509::ID_KP_CLIENT_AUTH,
+            Profile::CodeSigning => x509::ID_KP_CODE_SIGNING,
+            Profile::IntermediateCa => {
+                return ExtensionOptions {
+                    ca: true,
+                    path_len: Some(0),
+                    ..Default::default()
+                }
+            }
+        };
+        ExtensionOptions {
+            key_usage: Some(KeyUsages::DigitalSignature.into()),
+            extended_key_usage: vec![purpose],
+            ..Default::default()
+        }
+    }
+}
+
+impl FromStr for Profile {
+    type Err = CryptoError;
+
+    fn from_str(s: &str) -> Result<Self, CryptoError> {
+        match s.to_ascii_lowercase().as_str() {
+            "server" => Ok(Profile::Server),
+            "client" => Ok(Profile::Client),
+            "code-signing" => Ok(Profile::CodeSigning),
+            "intermediate-ca" => Ok(Profile::IntermediateCa),
+            _ => Err(CryptoError::InvalidArgument(format!(
+                "profile {:?}, expected server, client, code-signing or intermediate-ca",
+                s
+            ))),
+        }
+    }
+}
+
+/// Revocation of an issued certificate
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Revocation {
+    pub time: SystemTime,
+    pub reason: CrlReason,
+}
+
+/// One line of the index
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexEntry {
+    pub serial_number: Vec<u8>,
+    pub not_after: SystemTime,
+    pub revocation: Option<Revocation>,
+    /// Subject in the OpenSSL `/CN=name/O=org` form
+    pub subject: String,
+}
+
+impl IndexEntry {
+    fn parse(line: &str) -> Option<Self> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [status, not_after, revoked, serial, _file, subject] = fields[..] else {
+            return None;
+        };
+        let revocation = match status {
+            "V" | "E" if revoked.is_empty() => None,
+            "R" => {
+                let (time, reason) = match revoked.split_once(',') {
+                    Some((time, reason)) => (time, parse_reason(reason).ok()?),
+                    None => (revoked, CrlReason::Unspecified),
+                };
+                Some(Revocation {
+                    time: parse_index_time(time)?,
+                    reason,
+                })
+            }
+            _ => return None,
+        };
+        Some(IndexEntry {
+            serial_number: decode_serial(serial)?,
+            not_after: parse_index_time(not_after)?,
+            revocation,
+            subject: subject.to_string(),
+        })
+    }
+
+    fn to_line(&self) -> Result<String, CryptoError> {
+        let (status, revoked) = match &self.revocation {
+            None => ("V", String::new()),
+            Some(revocation) => {
+                let time = format_index_time(revocation.time)?;
+                match revocation.reason {
+                    CrlReason::Unspecified => ("R", time),
+                    reason => ("R", format!("{},{}", time, reason_name(reason))),
+                }
+            }
+        };
+        Ok(format!(
+            "{}\t{}\t{}\t{}\tunknown\t{}\n",
+            status,
+            format_index_time(self.not_after)?,
+            revoked,
+            encode_serial(&self.serial_number),
+            self.subject
+        ))
+    }
+}
+
+/// A CA directory, see the module documentation
+#[derive(Clone, Debug)]
+pub struct CertificateAuthority {
+    dir: PathBuf,
+    certificate: Certificate,
+}
+
+impl CertificateAuthority {
+    /// Set up `dir` for the CA `certificate`, storing `key_file` as the CA key. The directory
+    /// is created if needed and must not already hold a CA.
+    pub fn create(
+        dir: &Path,
+        certificate: &Certificate,
+        key_file: &[u8],
+    ) -> Result<Self, CryptoError> {
+        fs::create_dir_all(dir.join(CERTS_DIR))?;
+        if dir.join(CERTIFICATE_FILE).exists() {
+            return Err(CryptoError::CertificateAuthority(format!(
+                "{} already holds a CA",
+                dir.display()
+            )));
+        }
+        create_file(&dir.join(KEY_FILE), key_file)?;
+        create_file(&dir.join(INDEX_FILE), b"")?;
+        let serial = encode_serial(&x509::random_serial_number());
+        create_file(&dir.join(SERIAL_FILE), format!("{}\n", serial).as_bytes())?;
+        create_file(&dir.join(CRL_NUMBER_FILE), b"01\n")?;
+        create_file(
+            &dir.join(CERTIFICATE_FILE),
+            x509::to_pem(certificate)?.as_bytes(),
+        )?;
+        Ok(CertificateAuthority {
+            dir: dir.to_path_buf(),
+            certificate: certificate.clone(),
+        })
+    }
+
+    pub fn open(dir: &Path) -> Result<Self, CryptoError> {
+        let path = dir.join(CERTIFICATE_FILE);
+        if !path.exists() {
+            return Err(CryptoError::CertificateAuthority(format!(
+                "{} does not hold a CA",
+                dir.display()
+            )));
+        }
+        let certificate = x509::decode(&fs::read(&path)?)
+            .map_err(|err| err.in_file(&path.display().to_string()))?;
+        Ok(CertificateAuthority {
+            dir: dir.to_path_buf(),
+            certificate,
+        })
+    }
+
+    pub fn certificate(&self) -> &Certificate {
+        &self.certificate
+    }
+
+    pub fn key_path(&self) -> PathBuf {
+        self.dir.join(KEY_FILE)
+    }
+
+    /// Where the certificate with `serial_number` is kept
+    pub fn certificate_path(&self, serial_number: &[u8]) -> PathBuf {
+        self.dir
+            .join(CERTS_DIR)
+            .join(format!("{}.pem", encode_serial(serial_number)))
+    }
+
+    /// Issue a certificate for the key and subject of `request`, valid from now for `days`,
+    /// with the extensions of `profile` and the subjectAltName the request asks for. Other
+    /// requested extensions are ignored. The request signature must be valid and the
+    /// certificate must not outlive the CA's.
+    pub fn issue(
+        &self,
+        ca_key: &SecretKey,
+        request: &CertReq,
+        profile: Profile,
+        days: u32,
+    ) -> Result<Certificate, CryptoError> {
+        if !x509::verify_request(request)? {
+            return Err(CryptoError::CertificateAuthority(
+                "the signature on the request is invalid".to_string(),
+            ));
+        }
+        let mut options = CertificateOptions::new(request.info.subject.clone(), days);
+        options.extensions = profile.extensions();
+        for extension in x509::requested_extensions(request)? {
+            if extension.extn_id == SubjectAltName::OID {
+                let names = SubjectAltName::from_der(extension.extn_value.as_bytes())?;
+                options.extensions.subject_alt_names.extend(names.0);
+            }
+        }
+        let ca_not_after = self
+            .certificate
+            .tbs_certificate
+            .validity
+            .not_after
+            .to_system_time();
+        if options.not_after > ca_not_after {
+            return Err(CryptoError::CertificateAuthority(format!(
+                "the certificate would outlive the CA certificate, which expires {}",
+                self.certificate.tbs_certificate.validity.not_after
+            )));
+        }
+
+        let serial_number = self.next_serial_number()?;
+        let mut entries = self.entries()?;
+        if entries
+            .iter()
+            .any(|entry| entry.serial_number == serial_number)
+        {
+            return Err(CryptoError::CertificateAuthority(format!(
+                "serial number {} is already in the index",
+                encode_serial(&serial_number)
+            )));
+        }
+        options.serial_number = Some(serial_number.clone());
+        let subject_key = x509::request_public_key(request)?;
+        let certificate = x509::issue(ca_key, &self.certificate, &subject_key, &options)?;
+
+        create_file(
+            &self.certificate_path(&serial_number),
+            x509::to_pem(&certificate)?.as_bytes(),
+        )?;
+        entries.push(IndexEntry {
+            serial_number: serial_number.clone(),
+            not_after: certificate
+                .tbs_certificate
+                .validity
+                .not_after
+                .to_system_time(),
+            revocation: None,
+            subject: openssl_name(&certificate.tbs_certificate.subject),
+        });
+        self.write_index(&entries)?;
+        let next = encode_serial(&increment(serial_number));
+        replace_file(
+            &self.dir.join(SERIAL_FILE),
+            format!("{}\n", next).as_bytes(),
+        )?;
+        Ok(certificate)
+    }
+
+    /// Mark the certificate with `serial_number` as revoked at `time`
+    pub fn revoke(
+        &self,
+        serial_number: &[u8],
+        reason: CrlReason,
+        time: SystemTime,
+    ) -> Result<IndexEntry, CryptoError> {
+        let mut entries = self.entries()?;
+        let serial = encode_serial(serial_number);
+        let entry = entries
+            .iter_mut()
+            .find(|entry| entry.serial_number == serial_number)
+            .ok_or_else(|| {
+                CryptoError::CertificateAuthority(format!(
+                    "no certificate with serial number {}",
+                    serial
+                ))
+            })?;
+        if entry.revocation.is_some() {
+            return Err(CryptoError::CertificateAuthority(format!(
+                "certificate {} is already revoked",
+                serial
+            )));
+        }
+        entry.revocation = Some(Revocation { time, reason });
+        let revoked = entry.clone();
+        self.write_index(&entries)?;
+        Ok(revoked)
+    }
+
+    /// CRL listing the revoked certificates, valid from now for `days`. Each CRL gets the
+    /// next cRLNumber; a directory without a `crlnumber` file starts at 1.
+    pub fn crl(&self, ca_key: &SecretKey, days: u32) -> Result<CertificateList, CryptoError> {
+        let path = self.dir.join(CRL_NUMBER_FILE);
+        let number = match fs::read_to_string(&path) {
+            Ok(text) => decode_serial(text.trim()).ok_or_else(|| {
+                CryptoError::CertificateAuthority(format!(
+                    "{} does not hold a hex CRL number",
+                    path.display()
+                ))
+            })?,
+            Err(err) if err.kind() == ErrorKind::NotFound => vec![1],
+            Err(err) => return Err(err.into()),
+        };
+        let revoked = self
+            .entries()?
+            .into_iter()
+            .filter_map(|entry| {
+                let revocation = entry.revocation?;
+                Some(RevokedCertificate {
+                    serial_number: entry.serial_number,
+                    time: revocation.time,
+                    reason: revocation.reason,
+                })
+            })
+            .collect();
+        let this_update = SystemTime::now();
+        let crl = x509::crl(
+            ca_key,
+            &self.certificate,
+            &CrlOptions {
+                this_update,
+                next_update: this_update + Duration::from_secs(u64::from(days) * 24 * 60 * 60),
+                number: number.clone(),
+                revoked,
+            },
+        )?;
+        let next = encode_serial(&increment(number));
+        replace_file(&path, format!("{}\n", next).as_bytes())?;
+        Ok(crl)
+    }
+
+    /// Issued certificates, oldest first
+    pub fn entries(&self) -> Result<Vec<IndexEntry>, CryptoError> {
+        let path = self.dir.join(INDEX_FILE);
+        let text = fs::read_to_string(&path)?;
+        text.lines()
+            .enumerate()
+            .map(|(number, line)| {
+                IndexEntry::parse(line).ok_or_else(|| {
+                    CryptoError::CertificateAuthority(format!(
+                        "{} line {}: {:?}",
+                        path.display(),
+                        number + 1,
+                        line
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    fn next_serial_number(&self) -> Result<Vec<u8>, CryptoError> {
+        let path = self.dir.join(SERIAL_FILE);
+        let text = fs::read_to_string(&path)?;
+        decode_serial(text.trim()).ok_or_else(|| {
+            CryptoError::CertificateAuthority(format!(
+                "{} does not hold a hex serial number",
+                path.display()
+            ))
+        })
+    }
+
+    fn write_index(&self, entries: &[IndexEntry]) -> Result<(), CryptoError> {
+        let mut text = String::new();
+        for entry in entries {
+            text.push_str(&entry.to_line()?);
+        }
+        replace_file(&self.dir.join(INDEX_FILE), text.as_bytes())
+    }
+}
+
+/// Revocation reason by its RFC 5280 name, e.g. `keyCompromise`
+pub fn parse_reason(reason: &str) -> Result<CrlReason, CryptoError> {
+    REVOCATION_REASONS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(reason))
+        .map(|(_, reason)| *reason)
+        .ok_or_else(|| {
+            CryptoError::InvalidArgument(format!(
+                "revocation reason {:?}, expected one of {}",
+                reason,
+                REVOCATION_REASONS.map(|(name, _)| name).join(", ")
+            ))
+        })
+}
+
+/// Upper case hex, as `openssl ca` writes serial numbers
+pub fn encode_serial(serial_number: &[u8]) -> String {
+    serial_number.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+fn decode_serial(hex: &str) -> Option<Vec<u8>> {
+    if hex.is_empty() || !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// `serial_number + 1`, kept positive
+fn increment(mut serial_number: Vec<u8>) -> Vec<u8> {
+    for byte in serial_number.iter_mut().rev() {
+        let (sum, carry) = byte.overflowing_add(1);
+        *byte = sum;
+        if !carry {
+            break;
+        }
+    }
+    if serial_number.iter().all(|byte| *byte == 0) {
+        serial_number.insert(0, 1);
+    }
+    if serial_number[0] & 0x80 != 0 {
+        serial_number.insert(0, 0);
+    }
+    serial_number
+}
+
+/// `/CN=name/O=org`, the relative distinguished names in encoding order
+fn openssl_name(name: &x509::Name) -> String {
+    name.0.iter().map(|rdn| format!("/{}", rdn)).collect()
+}
+
+/// `YYMMDDHHMMSSZ` through 2049 and `YYYYMMDDHHMMSSZ` after, like the certificate times
+fn format_index_time(time: SystemTime) -> Result<String, CryptoError> {
+    let time = DateTime::from_system_time(time)?;
+    let year = if time.year() < 2050 {
+        format!("{:02}", time.year() % 100)
+    } else {
+        format!("{:04}", time.year())
+    };
+    Ok(format!(
+        "{}{:02}{:02}{:02}{:02}{:02}Z",
+        year,
+        time.month(),
+        time.day(),
+        time.hour(),
+        time.minutes(),
+        time.seconds()
+    ))
+}
+
+fn parse_index_time(time: &str) -> Option<SystemTime> {
+    let digits = time.strip_suffix('Z')?;
+    let field = |at: usize, len: usize| digits.get(at..at + len)?.parse::<u16>().ok();
+    let (year, rest) = match digits.len() {
+        12 => {
+            let year = field(0, 2)?;
+            (if year < 50 { 2000 + year } else { 1900 + year }, 2)
+        }
+        14 => (field(0, 4)?, 4),
+        _ => return None,
+    };
+    let part = |index: usize| field(rest + 2 * index, 2).and_then(|value| u8::try_from(value).ok());
+    DateTime::new(year, part(0)?, part(1)?, part(2)?, part(3)?, part(4)?)
+        .ok()
+        .map(|time| time.to_system_time())
+}
+
+/// Write a file that must not exist yet
+fn create_file(path: &Path, bytes: &[u8]) -> Result<(), CryptoError> {
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+    file.write_all(bytes)?;
+    Ok(())
+}
+
+/// Replace a file through a temporary one, so that it is never left half written
+fn replace_file(path: &Path, bytes: &[u8]) -> Result<(), CryptoError> {
+    let temporary = path.with_extension("tmp");
+    let mut file = File::create(&temporary)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    fs::rename(&temporary, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::Algorithm;
+    use x509_cert::ext::pkix::ExtendedKeyUsage;
+
+    fn ca_dir(name: &str) -> (PathBuf, SecretKey, CertificateAuthority) {
+        let dir = std::env::temp_dir().join(format!("crypto-ca-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let ca_key = SecretKey::from_seed(Algorithm::Mldsa65, &[1u8; 32]);
+        let mut options = CertificateOptions::new(x509::parse_name("CN=Test CA").unwrap(), 3650);
+        options.extensions.ca = true;
+        let certificate = x509::self_signed(&ca_key, &options).unwrap();
+        let ca =
+            CertificateAuthority::create(&dir, &certificate, ca_key.to_pem().unwrap().as_bytes())
+                .unwrap();
+        (dir, ca_key, ca)
+    }
+
+    #[test]
+    fn issue_and_revoke() {
+        let (dir, ca_key, _) = ca_dir("issue");
+        let ca = CertificateAuthority::open(&dir).unwrap();
+        assert!(SecretKey::from_pem(&fs::read(ca.key_path()).unwrap()).unwrap() == ca_key);
+
+        let leaf_key = SecretKey::from_seed(Algorithm::Mldsa44, &[2u8; 32]);
+        let extensions = ExtensionOptions {
+            subject_alt_names: vec![x509::parse_general_name("DNS:svc.example").unwrap()],
+            ..Default::default()
+        };
+        let request = x509::request(
+            &leaf_key,
+            &x509::parse_name("CN=svc,O=Example").unwrap(),
+            &extensions,
+        )
+        .unwrap();
+        let first = ca.issue(&ca_key, &request, Profile::Server, 30).unwrap();
+        let second = ca.issue(&ca_key, &request, Profile::Client, 30).unwrap();
+
+        let tbs = &first.tbs_certificate;
+        assert_eq!(tbs.issuer.to_string(), "CN=Test CA");
+        assert!(x509::verify_signature(&first, &ca_key.public_key()).unwrap());
+        let eku = x509::extension(&first, &ExtendedKeyUsage::OID).unwrap();
+        assert_eq!(
+            x509::describe_extension(eku),
+            "extKeyUsage: serverAuth".to_string()
+        );
+        let san = x509::extension(&first, &SubjectAltName::OID).unwrap();
+        assert_eq!(
+            x509::describe_extension(san),
+            "subjectAltName: DNS:svc.example"
+        );
+
+        let serial = tbs.serial_number.as_bytes().to_vec();
+        let next = second.tbs_certificate.serial_number.as_bytes().to_vec();
+        assert_eq!(increment(serial.clone()), next);
+        assert_eq!(
+            x509::decode(&fs::read(ca.certificate_path(&next)).unwrap()).unwrap(),
+            second
+        );
+
+        let entries = ca.entries().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].subject, "/O=Example/CN=svc");
+        assert!(entries.iter().all(|entry| entry.revocation.is_none()));
+
+        let time = x509::parse_time("2026-01-02T03:04:05Z").unwrap();
+        ca.revoke(&serial, CrlReason::KeyCompromise, time).unwrap();
+        assert!(matches!(
+            ca.revoke(&serial, CrlReason::KeyCompromise, time),
+            Err(CryptoError::CertificateAuthority(_))
+        ));
+        assert!(ca.revoke(&[0x7f], CrlReason::Unspecified, time).is_err());
+        let index = fs::read_to_string(dir.join(INDEX_FILE)).unwrap();
+        assert!(index.starts_with(&format!(
+            "R\t{}\t260102030405Z,keyCompromise\t{}\tunknown\t/O=Example/CN=svc\n",
+            format_index_time(entries[0].not_after).unwrap(),
+            encode_serial(&serial)
+        )));
+        assert_eq!(
+            ca.entries().unwrap()[0].revocation,
+            Some(Revocation {
+                time,
+                reason: CrlReason::KeyCompromise
+            })
+        );
+
+        let crl = ca.crl(&ca_key, 7).unwrap();
+        assert!(x509::verify_crl_signature(&crl, &ca_key.public_key()).unwrap());
+        assert_eq!(crl.tbs_cert_list.issuer, tbs.issuer);
+        let revoked = x509::revoked_certificates(&crl).unwrap();
+        assert_eq!(
+            revoked,
+            [RevokedCertificate {
+                serial_number: serial.clone(),
+                time,
+                reason: CrlReason::KeyCompromise
+            }]
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join(CRL_NUMBER_FILE)).unwrap(),
+            "02\n"
+        );
+        // Only the CA key signs CRLs
+        assert!(matches!(
+            ca.crl(&leaf_key, 7),
+            Err(CryptoError::KeyMismatch(_))
+        ));
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn issuing_is_constrained() {
+        let (dir, ca_key, ca) = ca_dir("constrained");
+        assert!(matches!(
+            CertificateAuthority::create(&dir, ca.certificate(), b""),
+            Err(CryptoError::CertificateAuthority(_))
+        ));
+        let leaf_key = SecretKey::from_seed(Algorithm::Mldsa44, &[2u8; 32]);
+        let subject = x509::parse_name("CN=sub").unwrap();
+        let mut request = x509::request(&leaf_key, &subject, &ExtensionOptions::default()).unwrap();
+
+        // The CA certificate expires in ten years
+        assert!(ca.issue(&ca_key, &request, Profile::Client, 4000).is_err());
+        let other_key = SecretKey::from_seed(Algorithm::Mldsa65, &[3u8; 32]);
+        assert!(matches!(
+            ca.issue(&other_key, &request, Profile::Client, 30),
+            Err(CryptoError::KeyMismatch(_))
+        ));
+
+        let intermediate = ca
+            .issue(&ca_key, &request, Profile::IntermediateCa, 30)
+            .unwrap();
+        let described: Vec<String> = intermediate
+            .tbs_certificate
+            .extensions
+            .iter()
+            .flatten()
+            .take(2)
+            .map(x509::describe_extension)
+            .collect();
+        assert_eq!(
+            described,
+            [
+                "basicConstraints (critical): cA=true, pathLen=0",
+                "keyUsage (critical): digitalSignature, keyCertSign, cRLSign",
+            ]
+        );
+        // pathLen 0: the intermediate cannot issue another CA
+        assert!(matches!(
+            x509::issue(
+                &leaf_key,
+                &intermediate,
+                &other_key.public_key(),
+                &CertificateOptions {
+                    extensions: Profile::IntermediateCa.extensions(),
+                    ..CertificateOptions::new(x509::parse_name("CN=deeper").unwrap(), 1)
+                }
+            ),
+            Err(CryptoError::InvalidCertificate(_))
+        ));
+
+        request.info.subject = x509::parse_name("CN=forged").unwrap();
+        assert!(matches!(
+            ca.issue(&ca_key, &request, Profile::Client, 30),
+            Err(CryptoError::CertificateAuthority(_))
+        ));
+        assert_eq!(ca.entries().unwrap().len(), 1);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn index_times_and_serials() {
+        for time in ["2026-01-02T03:04:05Z", "2060-12-31T23:59:59Z"] {
+            let time = x509::parse_time(time).unwrap();
+            let formatted = format_index_time(time).unwrap();
+            assert_eq!(parse_index_time(&formatted), Some(time));
+        }
+        assert_eq!(
+            format_index_time(x509::parse_time("2060-12-31T23:59:59Z").unwrap()).unwrap(),
+            "20601231235959Z"
+        );
+        assert_eq!(increment(vec![0x01, 0xff]), [0x02, 0x00]);
+        assert_eq!(increment(vec![0x7f, 0xff]), [0x00, 0x80, 0x00]);
+        assert_eq!(decode_serial("0A1b"), Some(vec![0x0a, 0x1b]));
+        assert_eq!(decode_serial("abc"), None);
+        assert_eq!(
+            parse_reason("cacompromise").unwrap(),
+            CrlReason::CaCompromise
+        );
+        assert!(parse_reason("certificateHold").is_err());
+        assert_eq!(
+            "Code-Signing".parse::<Profile>().unwrap(),
+            Profile::CodeSigning
+        );
+    }
+}
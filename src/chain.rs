@@ -0,0 +1,733 @@
+//! Certification path validation (RFC 5280 section 6) for certificates signed with the
+//! algorithms of this crate.
+//!
+//! A path is built from the leaf through the intermediates to a trust anchor by name chaining,
+//! skipping issuers whose subjectKeyIdentifier differs from the authorityKeyIdentifier. Each
+//! candidate path is then checked from the anchor down: signature, validity at the chosen
+//! time, issuer name, critical extensions and, for every issuing certificate, basicConstraints
+//! `cA`, its pathLen constraint and keyUsage `keyCertSign`. As in RFC 5280, a trust anchor only
+//! contributes its name, key and pathLen constraint; its own signature and validity are not
+//! checked. Policies, name constraints and subjectAltName are not processed, so a certificate
+//! that marks them critical fails.
+//!
+//! Revocation is checked against the CRLs given in [`ValidationOptions::crls`]: a CRL whose
+//! issuer is the issuing certificate must verify with its key and be current, and a certificate
+//! it lists as revoked by the validation time fails with [`CryptoError::Revoked`]. A certificate
+//! without a CRL from its issuer is not checked, as nothing is fetched.
+use crate::algorithm::Algorithm;
+use crate::error::CryptoError;
+use crate::keys::PublicKey;
+use crate::x509::{self, Certificate, CertificateList};
+use der::oid::AssociatedOid;
+use der::Decode;
+use std::time::SystemTime;
+use x509_cert::ext::pkix::{
+    AuthorityKeyIdentifier, BasicConstraints, ExtendedKeyUsage, KeyUsage, SubjectKeyIdentifier,
+};
+
+/// Certificates between the leaf and the trust anchor, at most
+const MAX_INTERMEDIATES: usize = 8;
+
+/// The check a certificate failed
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum PathCheck {
+    #[error("no intermediate or trust anchor is its issuer {0}")]
+    NoIssuer(String),
+    #[error("issuer name {issuer} does not match the issuing certificate's subject {expected}")]
+    IssuerName { issuer: String, expected: String },
+    #[error("unsupported signature algorithm {0}")]
+    UnsupportedAlgorithm(String),
+    #[error("signature algorithm {signature} does not match the issuer's {key} key")]
+    AlgorithmMismatch { signature: String, key: String },
+    #[error("the signature does not verify with the issuer's key")]
+    Signature,
+    #[error("not valid before {0}")]
+    NotYetValid(String),
+    #[error("expired at {0}")]
+    Expired(String),
+    #[error("unsupported critical extension {0}")]
+    CriticalExtension(String),
+    #[error("issues certificates but basicConstraints does not mark it as a CA")]
+    NotCa,
+    #[error("a pathLen constraint above it allows no further CA")]
+    PathLength,
+    #[error("issues certificates but its keyUsage lacks keyCertSign")]
+    KeyCertSign,
+    #[error("its keyUsage allows neither digitalSignature nor nonRepudiation")]
+    SigningKeyUsage,
+    #[error("its extKeyUsage includes neither codeSigning nor anyExtendedKeyUsage")]
+    SigningExtendedKeyUsage,
+    #[error("CRL of {issuer}: {problem}")]
+    Crl { issuer: String, problem: String },
+    #[error("malformed: {0}")]
+    Malformed(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct ValidationOptions {
+    /// Time at which every certificate of the path must be valid
+    pub time: SystemTime,
+    /// CRLs to check each certificate against, from any issuer
+    pub crls: Vec<CertificateList>,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        ValidationOptions {
+            time: SystemTime::now(),
+            crls: Vec::new(),
+        }
+    }
+}
+
+/// Validate `leaf` up to one of `anchors`. Returns the path, leaf first and trust anchor last.
+/// When no path validates, the error names the certificate and check that failed on the first
+/// path tried, or the certificate whose issuer could not be found.
+pub fn validate(
+    leaf: &Certificate,
+    intermediates: &[Certificate],
+    anchors: &[Certificate],
+    options: &ValidationOptions,
+) -> Result<Vec<Certificate>, CryptoError> {
+    if anchors.contains(leaf) {
+        return Ok(vec![leaf.clone()]);
+    }
+    let mut builder = Builder {
+        intermediates,
+        anchors,
+        paths: Vec::new(),
+        dead_end: vec![leaf],
+    };
+    builder.extend(vec![leaf]);
+    if builder.paths.is_empty() {
+        let stuck = builder
+            .dead_end
+            .last()
+            .expect("the leaf is always a dead end");
+        return Err(failure(
+            stuck,
+            PathCheck::NoIssuer(stuck.tbs_certificate.issuer.to_string()),
+        ));
+    }
+    let mut first_error = None;
+    for path in &builder.paths {
+        match check_path(path, options) {
+            Ok(()) => {
+                return Ok(path
+                    .iter()
+                    .map(|certificate| (*certificate).clone())
+                    .collect())
+            }
+            Err(err) => {
+                first_error.get_or_insert(err);
+            }
+        }
+    }
+    Err(first_error.expect("at least one path was checked"))
+}
+
+/// Check that the keyUsage of `certificate`, if any, allows signing other data than
+/// certificates and CRLs, and that its extKeyUsage, if any, allows code signing
+pub fn check_signing_usage(certificate: &Certificate) -> Result<(), CryptoError> {
+    let key_usage = extension::<KeyUsage>(certificate)?;
+    if key_usage.is_some_and(|usage| !usage.digital_signature() && !usage.non_repudiation()) {
+        return Err(failure(certificate, PathCheck::SigningKeyUsage));
+    }
+    let purposes = extension::<ExtendedKeyUsage>(certificate)?;
+    if purposes.is_some_and(|purposes| {
+        !purposes.0.contains(&x509::ID_KP_CODE_SIGNING)
+            && !purposes.0.contains(&x509::ANY_EXTENDED_KEY_USAGE)
+    }) {
+        return Err(failure(certificate, PathCheck::SigningExtendedKeyUsage));
+    }
+    Ok(())
+}
+
+fn failure(certificate: &Certificate, check: PathCheck) -> CryptoError {
+    let subject = certificate.tbs_certificate.subject.to_string();
+    CryptoError::PathValidation {
+        subject: if subject.is_empty() {
+            "(empty subject)".to_string()
+        } else {
+            subject
+        },
+        check,
+    }
+}
+
+/// Candidate paths by name chaining, each leaf first and ending with a trust anchor
+struct Builder<'a> {
+    intermediates: &'a [Certificate],
+    anchors: &'a [Certificate],
+    paths: Vec<Vec<&'a Certificate>>,
+    /// Longest chain that could not be extended
+    dead_end: Vec<&'a Certificate>,
+}
+
+impl<'a> Builder<'a> {
+    fn extend(&mut self, chain: Vec<&'a Certificate>) {
+        let last = *chain.last().expect("chains start with the leaf");
+        let mut extended = false;
+        for anchor in self.anchors.iter().filter(|anchor| issued_by(last, anchor)) {
+            let mut path = chain.clone();
+            path.push(anchor);
+            self.paths.push(path);
+            extended = true;
+        }
+        if chain.len() <= MAX_INTERMEDIATES {
+            for intermediate in self.intermediates.iter() {
+                if issued_by(last, intermediate) && !chain.contains(&intermediate) {
+                    let mut longer = chain.clone();
+                    longer.push(intermediate);
+                    self.extend(longer);
+                    extended = true;
+                }
+            }
+        }
+        if !extended && chain.len() > self.dead_end.len() {
+            self.dead_end = chain;
+        }
+    }
+}
+
+/// Whether `issuer` may have issued `certificate`: the names chain and, when both are present,
+/// the authority and subject key identifiers agree
+fn issued_by(certificate: &Certificate, issuer: &Certificate) -> bool {
+    if certificate.tbs_certificate.issuer != issuer.tbs_certificate.subject {
+        return false;
+    }
+    let authority = extension::<AuthorityKeyIdentifier>(certificate)
+        .ok()
+        .flatten()
+        .and_then(|aki| aki.key_identifier);
+    let subject = extension::<SubjectKeyIdentifier>(issuer).ok().flatten();
+    match (authority, subject) {
+        (Some(authority), Some(subject)) => authority == subject.0,
+        _ => true,
+    }
+}
+
+/// RFC 5280 section 6.1 over `path`, leaf first and trust anchor last
+fn check_path(path: &[&Certificate], options: &ValidationOptions) -> Result<(), CryptoError> {
+    let (anchor, certificates) = path.split_last().expect("paths end with a trust anchor");
+    let malformed = |certificate: &Certificate, err: CryptoError| {
+        failure(certificate, PathCheck::Malformed(err.to_string()))
+    };
+    let mut issuer = *anchor;
+    let mut issuer_key = x509::public_key(anchor).map_err(|err| malformed(anchor, err))?;
+    let mut max_path_len = extension::<BasicConstraints>(anchor)
+        .map_err(|err| malformed(anchor, err))?
+        .and_then(|basic| basic.path_len_constraint)
+        .map_or(usize::MAX, usize::from);
+
+    for (depth, certificate) in certificates.iter().rev().enumerate() {
+        let fail = |check| Err(failure(certificate, check));
+        let tbs = &certificate.tbs_certificate;
+
+        let oid = certificate.signature_algorithm.oid;
+        if Algorithm::from_oid(&oid).is_err() {
+            return fail(PathCheck::UnsupportedAlgorithm(oid.to_string()));
+        }
+        if oid != issuer_key.algorithm().oid() {
+            return fail(PathCheck::AlgorithmMismatch {
+                signature: Algorithm::from_oid(&oid)?.name().to_string(),
+                key: issuer_key.algorithm().name().to_string(),
+            });
+        }
+        match x509::verify_signature(certificate, &issuer_key) {
+            Ok(true) => {}
+            Ok(false) | Err(CryptoError::SignatureSizeMismatch { .. }) => {
+                return fail(PathCheck::Signature)
+            }
+            Err(err) => return Err(malformed(certificate, err)),
+        }
+
+        if tbs.validity.not_before.to_system_time() > options.time {
+            return fail(PathCheck::NotYetValid(tbs.validity.not_before.to_string()));
+        }
+        if tbs.validity.not_after.to_system_time() < options.time {
+            return fail(PathCheck::Expired(tbs.validity.not_after.to_string()));
+        }
+        if tbs.issuer != issuer.tbs_certificate.subject {
+            return fail(PathCheck::IssuerName {
+                issuer: tbs.issuer.to_string(),
+                expected: issuer.tbs_certificate.subject.to_string(),
+            });
+        }
+        for extension in tbs.extensions.iter().flatten() {
+            if extension.critical && !is_processed(&extension.extn_id) {
+                return fail(PathCheck::CriticalExtension(extension.extn_id.to_string()));
+            }
+        }
+        check_revocation(certificate, issuer, &issuer_key, options)?;
+
+        // Every certificate but the leaf issues the next one
+        if depth + 1 < certificates.len() {
+            let basic = extension::<BasicConstraints>(certificate)
+                .map_err(|err| malformed(certificate, err))?;
+            let Some(basic) = basic.filter(|basic| basic.ca) else {
+                return fail(PathCheck::NotCa);
+            };
+            // Self-issued certificates, such as key rollover ones, do not count
+            if tbs.subject != tbs.issuer {
+                if max_path_len == 0 {
+                    return fail(PathCheck::PathLength);
+                }
+                max_path_len -= 1;
+            }
+            if let Some(path_len) = basic.path_len_constraint {
+                max_path_len = max_path_len.min(usize::from(path_len));
+            }
+            let key_usage =
+                extension::<KeyUsage>(certificate).map_err(|err| malformed(certificate, err))?;
+            if key_usage.is_some_and(|usage| !usage.key_cert_sign()) {
+                return fail(PathCheck::KeyCertSign);
+            }
+        }
+        issuer = certificate;
+        issuer_key = x509::public_key(certificate).map_err(|err| malformed(certificate, err))?;
+    }
+    Ok(())
+}
+
+/// Check `certificate` against the CRLs of `issuer`, whose key is `issuer_key`
+fn check_revocation(
+    certificate: &Certificate,
+    issuer: &Certificate,
+    issuer_key: &PublicKey,
+    options: &ValidationOptions,
+) -> Result<(), CryptoError> {
+    let issuer_name = &issuer.tbs_certificate.subject;
+    let crls = options
+        .crls
+        .iter()
+        .filter(|crl| crl.tbs_cert_list.issuer == *issuer_name);
+    for crl in crls {
+        let crl_failure = |problem: String| {
+            failure(
+                certificate,
+                PathCheck::Crl {
+                    issuer: issuer_name.to_string(),
+                    problem,
+                },
+            )
+        };
+        let key_usage = extension::<KeyUsage>(issuer)
+            .map_err(|err| failure(issuer, PathCheck::Malformed(err.to_string())))?;
+        if key_usage.is_some_and(|usage| !usage.crl_sign()) {
+            return Err(crl_failure(
+                "the issuer's keyUsage lacks cRLSign".to_string(),
+            ));
+        }
+        match x509::verify_crl_signature(crl, issuer_key) {
+            Ok(true) => {}
+            Ok(false) | Err(CryptoError::SignatureSizeMismatch { .. }) => {
+                return Err(crl_failure(
+                    "the signature does not verify with the issuer's key".to_string(),
+                ))
+            }
+            Err(err) => return Err(crl_failure(err.to_string())),
+        }
+        let tbs = &crl.tbs_cert_list;
+        if tbs.this_update.to_system_time() > options.time {
+            return Err(crl_failure(format!("not valid before {}", tbs.this_update)));
+        }
+        if let Some(next_update) = tbs.next_update {
+            if next_update.to_system_time() < options.time {
+                return Err(crl_failure(format!("out of date since {}", next_update)));
+            }
+        }
+
+        let revoked =
+            x509::revoked_certificates(crl).map_err(|err| crl_failure(err.to_string()))?;
+        let serial_number = certificate.tbs_certificate.serial_number.as_bytes();
+        if let Some(entry) = revoked
+            .iter()
+            .find(|entry| entry.serial_number == serial_number && entry.time <= options.time)
+        {
+            let time = der::DateTime::from_system_time(entry.time)?;
+            return Err(CryptoError::Revoked {
+                subject: certificate.tbs_certificate.subject.to_string(),
+                serial: crate::ca::encode_serial(serial_number),
+                time: time.to_string(),
+                reason: x509::reason_name(entry.reason).to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Extensions this module understands, which may therefore be critical. The extKeyUsage of
+/// a signer is checked by [`check_signing_usage`].
+fn is_processed(oid: &der::asn1::ObjectIdentifier) -> bool {
+    [
+        BasicConstraints::OID,
+        KeyUsage::OID,
+        ExtendedKeyUsage::OID,
+        SubjectKeyIdentifier::OID,
+        AuthorityKeyIdentifier::OID,
+    ]
+    .contains(oid)
+}
+
+/// Extension `T` of `certificate`, decoded
+fn extension<'a, T: AssociatedOid + Decode<'a>>(
+    certificate: &'a Certificate,
+) -> Result<Option<T>, CryptoError> {
+    x509::extension(certificate, &T::OID)
+        .map(|extension| T::from_der(extension.extn_value.as_bytes()))
+        .transpose()
+        .map_err(CryptoError::DerDecode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::SecretKey;
+    use crate::x509::{
+        CertificateOptions, CrlOptions, CrlReason, ExtensionOptions, RevokedCertificate,
+    };
+    use der::Encode;
+    use std::time::Duration;
+    use x509_cert::ext::pkix::SubjectAltName;
+
+    struct Pki {
+        root_key: SecretKey,
+        root: Certificate,
+        intermediate_key: SecretKey,
+        intermediate: Certificate,
+        leaf_key: SecretKey,
+        leaf: Certificate,
+    }
+
+    fn options(subject: &str, ca: bool) -> CertificateOptions {
+        let mut options = CertificateOptions::new(x509::parse_name(subject).unwrap(), 30);
+        options.extensions.ca = ca;
+        options
+    }
+
+    fn pki() -> Pki {
+        let root_key = SecretKey::from_seed(Algorithm::Mldsa87, &[1u8; 32]);
+        let intermediate_key = SecretKey::from_seed(Algorithm::Mldsa65, &[2u8; 32]);
+        let leaf_key = SecretKey::from_seed(Algorithm::Dilithium2, &[3u8; 32]);
+        let root = x509::self_signed(&root_key, &options("CN=Root", true)).unwrap();
+        let intermediate = x509::issue(
+            &root_key,
+            &root,
+            &intermediate_key.public_key(),
+            &options("CN=Intermediate", true),
+        )
+        .unwrap();
+        let leaf = x509::issue(
+            &intermediate_key,
+            &intermediate,
+            &leaf_key.public_key(),
+            &options("CN=Leaf", false),
+        )
+        .unwrap();
+        Pki {
+            root_key,
+            root,
+            intermediate_key,
+            intermediate,
+            leaf_key,
+            leaf,
+        }
+    }
+
+    fn check(result: Result<Vec<Certificate>, CryptoError>) -> (String, PathCheck) {
+        match result {
+            Err(CryptoError::PathValidation { subject, check }) => (subject, check),
+            other => panic!("unexpected result: {:?}", other.map(|path| path.len())),
+        }
+    }
+
+    #[test]
+    fn valid_path_is_built() {
+        let pki = pki();
+        let other = x509::self_signed(
+            &SecretKey::from_seed(Algorithm::Mldsa44, &[4u8; 32]),
+            &options("CN=Other", true),
+        )
+        .unwrap();
+        let options = ValidationOptions::default();
+        let anchors = [pki.root.clone()];
+        let intermediates = [pki.intermediate.clone()];
+        let path = validate(
+            &pki.leaf,
+            &[other.clone(), pki.intermediate.clone()],
+            &[other.clone(), pki.root.clone()],
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            path,
+            [pki.leaf.clone(), pki.intermediate.clone(), pki.root.clone()]
+        );
+        assert!(check_signing_usage(&pki.leaf).is_ok());
+
+        let (subject, failed) = check(validate(&pki.leaf, &[], &anchors, &options));
+        assert_eq!(subject, "CN=Leaf");
+        assert_eq!(failed, PathCheck::NoIssuer("CN=Intermediate".to_string()));
+        let (subject, failed) = check(validate(&pki.leaf, &intermediates, &[other], &options));
+        assert_eq!(subject, "CN=Intermediate");
+        assert_eq!(failed, PathCheck::NoIssuer("CN=Root".to_string()));
+    }
+
+    #[test]
+    fn each_check_is_reported() {
+        let pki = pki();
+        let anchors = [pki.root.clone()];
+        let intermediates = [pki.intermediate.clone()];
+
+        let expired = ValidationOptions {
+            time: SystemTime::now() + Duration::from_secs(60 * 24 * 60 * 60),
+            ..Default::default()
+        };
+        let (subject, failed) = check(validate(&pki.leaf, &intermediates, &anchors, &expired));
+        assert_eq!(subject, "CN=Intermediate");
+        assert!(matches!(failed, PathCheck::Expired(_)));
+        let early = ValidationOptions {
+            time: x509::parse_time("2020-01-01T00:00:00Z").unwrap(),
+            ..Default::default()
+        };
+        let (_, failed) = check(validate(&pki.leaf, &intermediates, &anchors, &early));
+        assert!(matches!(failed, PathCheck::NotYetValid(_)));
+
+        let mut forged = pki.leaf.clone();
+        forged.tbs_certificate.subject = x509::parse_name("CN=Forged").unwrap();
+        let (subject, failed) = check(validate(
+            &forged,
+            &intermediates,
+            &anchors,
+            &ValidationOptions::default(),
+        ));
+        assert_eq!(
+            (subject.as_str(), failed),
+            ("CN=Forged", PathCheck::Signature)
+        );
+
+        // An end-entity certificate cannot issue
+        let end_entity = x509::issue(
+            &pki.root_key,
+            &pki.root,
+            &pki.intermediate_key.public_key(),
+            &options("CN=Intermediate", false),
+        );
+        let end_entity = end_entity.unwrap();
+        let (subject, failed) = check(validate(
+            &pki.leaf,
+            &[end_entity],
+            &anchors,
+            &ValidationOptions::default(),
+        ));
+        assert_eq!(
+            (subject.as_str(), failed),
+            ("CN=Intermediate", PathCheck::NotCa)
+        );
+
+        // keyCertSign missing from a CA
+        let mut no_cert_sign = options("CN=Intermediate", true);
+        no_cert_sign.extensions.key_usage = Some(x509::parse_key_usage("cRLSign").unwrap());
+        let no_cert_sign = x509::issue(
+            &pki.root_key,
+            &pki.root,
+            &pki.intermediate_key.public_key(),
+            &no_cert_sign,
+        )
+        .unwrap();
+        let (_, failed) = check(validate(
+            &pki.leaf,
+            &[no_cert_sign],
+            &anchors,
+            &ValidationOptions::default(),
+        ));
+        assert_eq!(failed, PathCheck::KeyCertSign);
+
+        // pathLen 0 on the root leaves no room for the intermediate
+        let mut root_options = options("CN=Root", true);
+        root_options.extensions.path_len = Some(0);
+        let strict_root = x509::self_signed(&pki.root_key, &root_options).unwrap();
+        let (_, failed) = check(validate(
+            &pki.leaf,
+            &intermediates,
+            &[strict_root],
+            &ValidationOptions::default(),
+        ));
+        assert_eq!(failed, PathCheck::PathLength);
+
+        let code_signing = x509::issue(
+            &pki.intermediate_key,
+            &pki.intermediate,
+            &pki.leaf_key.public_key(),
+            &CertificateOptions {
+                extensions: ExtensionOptions {
+                    key_usage: Some(x509::parse_key_usage("cRLSign").unwrap()),
+                    ..Default::default()
+                },
+                ..options("CN=Leaf", false)
+            },
+        )
+        .unwrap();
+        assert!(matches!(
+            check_signing_usage(&code_signing),
+            Err(CryptoError::PathValidation {
+                check: PathCheck::SigningKeyUsage,
+                ..
+            })
+        ));
+        let with_purposes = |purposes: Vec<der::asn1::ObjectIdentifier>| {
+            let mut leaf_options = options("CN=Leaf", false);
+            leaf_options.extensions.extended_key_usage = purposes;
+            x509::issue(
+                &pki.intermediate_key,
+                &pki.intermediate,
+                &pki.leaf_key.public_key(),
+                &leaf_options,
+            )
+            .unwrap()
+        };
+        assert!(matches!(
+            check_signing_usage(&with_purposes(vec![x509::ID_KP_SERVER_AUTH])),
+            Err(CryptoError::PathValidation {
+                check: PathCheck::SigningExtendedKeyUsage,
+                ..
+            })
+        ));
+        for purpose in [x509::ID_KP_CODE_SIGNING, x509::ANY_EXTENDED_KEY_USAGE] {
+            let leaf = with_purposes(vec![x509::ID_KP_SERVER_AUTH, purpose]);
+            assert!(check_signing_usage(&leaf).is_ok());
+        }
+
+        // A critical subjectAltName, as for an empty subject, is not processed
+        let mut san_options = options("CN=Leaf", false);
+        san_options.extensions.subject_alt_names =
+            vec![x509::parse_general_name("DNS:svc.example").unwrap()];
+        let mut critical_san = x509::issue(
+            &pki.intermediate_key,
+            &pki.intermediate,
+            &pki.leaf_key.public_key(),
+            &san_options,
+        )
+        .unwrap();
+        let tbs = &mut critical_san.tbs_certificate;
+        for extension in tbs.extensions.iter_mut().flatten() {
+            extension.critical |= extension.extn_id == SubjectAltName::OID;
+        }
+        let signature = pki.intermediate_key.sign(&tbs.to_der().unwrap()).unwrap();
+        critical_san.signature = der::asn1::BitString::from_bytes(signature.as_bytes()).unwrap();
+        let (_, failed) = check(validate(
+            &critical_san,
+            &intermediates,
+            &anchors,
+            &ValidationOptions::default(),
+        ));
+        assert_eq!(
+            failed,
+            PathCheck::CriticalExtension(SubjectAltName::OID.to_string())
+        );
+    }
+
+    #[test]
+    fn revoked_certificates_fail() {
+        let pki = pki();
+        let anchors = [pki.root.clone()];
+        let intermediates = [pki.intermediate.clone()];
+        let now = SystemTime::now();
+        let crl = |revoked: Vec<RevokedCertificate>| {
+            x509::crl(
+                &pki.intermediate_key,
+                &pki.intermediate,
+                &CrlOptions {
+                    this_update: now - Duration::from_secs(60),
+                    next_update: now + Duration::from_secs(24 * 60 * 60),
+                    number: vec![1],
+                    revoked,
+                },
+            )
+            .unwrap()
+        };
+        let with_crls = |crls: Vec<CertificateList>| ValidationOptions {
+            crls,
+            ..Default::default()
+        };
+        let leaf_serial = pki.leaf.tbs_certificate.serial_number.as_bytes().to_vec();
+        let revoked = RevokedCertificate {
+            serial_number: leaf_serial.clone(),
+            time: now - Duration::from_secs(30),
+            reason: CrlReason::KeyCompromise,
+        };
+
+        let empty = crl(Vec::new());
+        assert!(empty.tbs_cert_list.revoked_certificates.is_none());
+        assert!(validate(&pki.leaf, &intermediates, &anchors, &with_crls(vec![empty])).is_ok());
+
+        let listing = crl(vec![revoked.clone()]);
+        let entries = x509::revoked_certificates(&listing).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            (&entries[0].serial_number, entries[0].reason),
+            (&leaf_serial, CrlReason::KeyCompromise)
+        );
+        match validate(
+            &pki.leaf,
+            &intermediates,
+            &anchors,
+            &with_crls(vec![listing.clone()]),
+        ) {
+            Err(CryptoError::Revoked {
+                subject, reason, ..
+            }) => assert_eq!(
+                (subject.as_str(), reason.as_str()),
+                ("CN=Leaf", "keyCompromise")
+            ),
+            other => panic!("unexpected result: {:?}", other.map(|path| path.len())),
+        }
+        // A CRL from another issuer does not apply
+        let root_crl = x509::crl(
+            &pki.root_key,
+            &pki.root,
+            &CrlOptions {
+                this_update: now - Duration::from_secs(60),
+                next_update: now + Duration::from_secs(24 * 60 * 60),
+                number: vec![1],
+                revoked: vec![revoked.clone()],
+            },
+        )
+        .unwrap();
+        assert!(validate(
+            &pki.leaf,
+            &intermediates,
+            &anchors,
+            &with_crls(vec![root_crl])
+        )
+        .is_ok());
+
+        // Revoked after the validation time
+        let later = crl(vec![RevokedCertificate {
+            time: now + Duration::from_secs(60 * 60),
+            ..revoked.clone()
+        }]);
+        assert!(validate(&pki.leaf, &intermediates, &anchors, &with_crls(vec![later])).is_ok());
+
+        let mut forged = listing.clone();
+        forged.tbs_cert_list.revoked_certificates = None;
+        let (subject, failed) = check(validate(
+            &pki.leaf,
+            &intermediates,
+            &anchors,
+            &with_crls(vec![forged]),
+        ));
+        assert_eq!(subject, "CN=Leaf");
+        assert!(matches!(failed, PathCheck::Crl { issuer, .. } if issuer == "CN=Intermediate"));
+
+        let out_of_date = ValidationOptions {
+            time: now + Duration::from_secs(2 * 24 * 60 * 60),
+            crls: vec![crl(Vec::new())],
+        };
+        let (_, failed) = check(validate(&pki.leaf, &intermediates, &anchors, &out_of_date));
+        assert!(
+            matches!(failed, PathCheck::Crl { problem, .. } if problem.starts_with("out of date"))
+        );
+    }
+}
@@ -0,0 +1,525 @@
+//! CMS `SignedData` (RFC 5652) with ML-DSA signers, following the LAMPS profile (RFC 9882):
+//! pure ML-DSA with an empty context over the DER signed attributes, SHA-512 message digest.
+//!
+//! Signers are identified by `subjectKeyIdentifier`: that of the signer certificate when it is
+//! included, otherwise the one computed from the key, see [`PublicKey::subject_key_identifier`].
+use crate::algorithm::Algorithm;
+use crate::armor;
+use crate::asc1_dilithium::{
+    AlgorithmIdentifier, Attribute, ContentInfo, DigestAlgorithmIdentifier,
+    EncapsulatedContentInfo, SignedData, SignerIdentifier, SignerInfo, OID_ATTR_CONTENT_TYPE,
+    OID_ATTR_MESSAGE_DIGEST, OID_ATTR_SIGNING_TIME, OID_CMS_DATA, OID_CMS_SIGNED_DATA, OID_SHA256,
+    OID_SHA384, OID_SHA512,
+};
+use crate::error::CryptoError;
+use crate::keys::{PublicKey, SecretKey, Signature};
+use crate::x509::{self, Certificate};
+use der::asn1::{AnyRef, ObjectIdentifier, OctetStringRef, SetOfVec, UtcTime};
+use der::pem::PemLabel;
+use der::{Decode, Encode};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use std::time::SystemTime;
+
+/// PEM labels accepted for a CMS message; OpenSSL writes `CMS`, older tools `PKCS7`
+const PEM_LABELS: [&str; 2] = [ContentInfo::PEM_LABEL, "PKCS7"];
+
+#[derive(Clone, Debug, Default)]
+pub struct CmsSignOptions {
+    /// Leave the content out of the message (`eContent` absent)
+    pub detached: bool,
+    /// Add a `signingTime` signed attribute
+    pub signing_time: Option<SystemTime>,
+    /// Certificate of the signing key, included in the message
+    pub certificate: Option<Certificate>,
+}
+
+/// Sign `content` as id-data and return the DER `ContentInfo`. The key must be a pure ML-DSA
+/// one, the only kind RFC 9882 defines for CMS.
+pub fn sign(
+    secret_key: &SecretKey,
+    content: &[u8],
+    options: &CmsSignOptions,
+) -> Result<Vec<u8>, CryptoError> {
+    let public_key = secret_key.public_key();
+    if !matches!(
+        public_key.algorithm(),
+        Algorithm::Mldsa44 | Algorithm::Mldsa65 | Algorithm::Mldsa87
+    ) {
+        return Err(CryptoError::InvalidAlgorithm(format!(
+            "{} in CMS, RFC 9882 only defines pure ML-DSA signers",
+            public_key.algorithm()
+        )));
+    }
+    let ski = match &options.certificate {
+        Some(certificate) => {
+            if x509::public_key(certificate)? != public_key {
+                return Err(CryptoError::KeyMismatch(
+                    "the private key does not belong to the signer certificate".to_string(),
+                ));
+            }
+            x509::key_identifier(certificate)?
+        }
+        None => public_key.subject_key_identifier().to_vec(),
+    };
+    let certificate = options
+        .certificate
+        .as_ref()
+        .map(|certificate| certificate.to_der())
+        .transpose()?;
+
+    let content_type = ObjectIdentifier::new_unwrap(OID_CMS_DATA).to_der()?;
+    let message_digest = OctetStringRef::new(&Sha512::digest(content))?.to_der()?;
+    let signing_time = options
+        .signing_time
+        .map(|time| UtcTime::from_system_time(time)?.to_der())
+        .transpose()?;
+
+    let mut signed_attrs = SetOfVec::new();
+    signed_attrs.insert(attribute(OID_ATTR_CONTENT_TYPE, &content_type)?)?;
+    signed_attrs.insert(attribute(OID_ATTR_MESSAGE_DIGEST, &message_digest)?)?;
+    if let Some(signing_time) = &signing_time {
+        signed_attrs.insert(attribute(OID_ATTR_SIGNING_TIME, signing_time)?)?;
+    }
+    let signature = secret_key.sign(&signed_attrs.to_der()?)?;
+
+    let digest_algorithm = DigestAlgorithmIdentifier {
+        algorithm: ObjectIdentifier::new_unwrap(OID_SHA512),
+        parameters: None,
+    };
+    let signer_info = SignerInfo {
+        version: 3,
+        sid: SignerIdentifier::SubjectKeyIdentifier(OctetStringRef::new(&ski)?),
+        digest_algorithm: digest_algorithm.clone(),
+        signed_attrs: Some(signed_attrs),
+        signature_algorithm: AlgorithmIdentifier {
+            algorithm: public_key.algorithm().oid(),
+        },
+        signature: OctetStringRef::new(signature.as_bytes())?,
+        unsigned_attrs: None,
+    };
+    let signed_data = SignedData {
+        version: 3,
+        digest_algorithms: SetOfVec::try_from(vec![digest_algorithm])?,
+        encap_content_info: EncapsulatedContentInfo {
+            e_content_type: ObjectIdentifier::new_unwrap(OID_CMS_DATA),
+            e_content: (!options.detached)
+                .then(|| OctetStringRef::new(content))
+                .transpose()?,
+        },
+        certificates: certificate
+            .as_deref()
+            .map(|certificate| SetOfVec::try_from(vec![AnyRef::from_der(certificate)?]))
+            .transpose()?,
+        crls: None,
+        signer_infos: SetOfVec::try_from(vec![signer_info])?,
+    }
+    .to_der()?;
+    let content_info = ContentInfo {
+        content_type: ObjectIdentifier::new_unwrap(OID_CMS_SIGNED_DATA),
+        content: AnyRef::from_der(&signed_data)?,
+    };
+    Ok(content_info.to_der()?)
+}
+
+/// Check the signature of `public_key` in the DER `ContentInfo` `message`. `detached` is the
+/// content of a detached signature. `Ok(false)` means the signature or message digest is invalid.
+pub fn verify(
+    message: &[u8],
+    public_key: &PublicKey,
+    detached: Option<&[u8]>,
+) -> Result<bool, CryptoError> {
+    let content_info = ContentInfo::from_der(message).map_err(CryptoError::DerDecode)?;
+    let signed_data = signed_data(&content_info)?;
+    let encap = &signed_data.encap_content_info;
+    let content = match (encap.e_content, detached) {
+        (Some(content), None) => content.as_bytes(),
+        (None, Some(content)) => content,
+        (Some(_), Some(_)) => {
+            return Err(CryptoError::InvalidCms(
+                "the content is encapsulated, a detached content cannot be given".to_string(),
+            ))
+        }
+        (None, None) => {
+            return Err(CryptoError::InvalidCms(
+                "the signature is detached, the content is required".to_string(),
+            ))
+        }
+    };
+
+    let signer_info = find_signer(&signed_data, public_key)?;
+    if signer_info.signature_algorithm.algorithm != public_key.algorithm().oid() {
+        return Err(CryptoError::InvalidAlgorithm(
+            signer_info.signature_algorithm.algorithm.to_string(),
+        ));
+    }
+    let signed = match &signer_info.signed_attrs {
+        Some(signed_attrs) => {
+            let content_type = single_value(signed_attrs, OID_ATTR_CONTENT_TYPE)?
+                .decode_as::<ObjectIdentifier>()
+                .map_err(CryptoError::DerDecode)?;
+            let message_digest = single_value(signed_attrs, OID_ATTR_MESSAGE_DIGEST)?
+                .decode_as::<OctetStringRef<'_>>()
+                .map_err(CryptoError::DerDecode)?;
+            if content_type != encap.e_content_type {
+                return Err(CryptoError::InvalidCms(
+                    "the contentType attribute does not match the content".to_string(),
+                ));
+            }
+            if message_digest.as_bytes() != digest(&signer_info.digest_algorithm, content)? {
+                return Ok(false);
+            }
+            signed_attrs.to_der()?
+        }
+        None => content.to_vec(),
+    };
+    let signature =
+        Signature::from_bytes(public_key.algorithm(), signer_info.signature.as_bytes())?;
+    public_key.verify(&signed, &signature)
+}
+
+/// Encapsulated content of the DER `ContentInfo` `message`, `None` for a detached signature
+pub fn content(message: &[u8]) -> Result<Option<Vec<u8>>, CryptoError> {
+    let content_info = ContentInfo::from_der(message).map_err(CryptoError::DerDecode)?;
+    let signed_data = signed_data(&content_info)?;
+    Ok(signed_data
+        .encap_content_info
+        .e_content
+        .map(|content| content.as_bytes().to_vec()))
+}
+
+/// DER of a PEM (`CMS` or `PKCS7`) or DER CMS message
+pub fn decode(bytes: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    if !bytes.trim_ascii_start().starts_with(b"-----BEGIN ") {
+        return Ok(bytes.to_vec());
+    }
+    let (label, der) = armor::decode(bytes)?;
+    if !PEM_LABELS.contains(&label.as_str()) {
+        return Err(CryptoError::PemDecode(der::pem::Error::Label.into()));
+    }
+    Ok(der)
+}
+
+pub fn to_pem(message: &[u8]) -> String {
+    armor::encode(ContentInfo::PEM_LABEL, message)
+}
+
+fn attribute<'a>(oid: &str, value: &'a [u8]) -> Result<Attribute<'a>, CryptoError> {
+    Ok(Attribute {
+        attr_type: ObjectIdentifier::new_unwrap(oid),
+        attr_values: SetOfVec::try_from(vec![AnyRef::from_der(value)?])?,
+    })
+}
+
+fn signed_data<'a>(content_info: &ContentInfo<'a>) -> Result<SignedData<'a>, CryptoError> {
+    if content_info.content_type != ObjectIdentifier::new_unwrap(OID_CMS_SIGNED_DATA) {
+        return Err(CryptoError::InvalidCms(format!(
+            "content type {} is not signedData",
+            content_info.content_type
+        )));
+    }
+    content_info
+        .content
+        .decode_as()
+        .map_err(CryptoError::DerDecode)
+}
+
+/// The signer identified by the `subjectKeyIdentifier` computed from `public_key`, or by the
+/// key identifier or issuer and serial number of an included certificate for the key
+fn find_signer<'a, 'b>(
+    signed_data: &'b SignedData<'a>,
+    public_key: &PublicKey,
+) -> Result<&'b SignerInfo<'a>, CryptoError> {
+    let ski = public_key.subject_key_identifier();
+    let mut key_identifiers = vec![ski.to_vec()];
+    let mut issuers_and_serials = Vec::new();
+    let certificates = signed_data.certificates.as_ref();
+    for certificate in certificates.map(SetOfVec::as_slice).unwrap_or_default() {
+        // Attribute certificates and other choices are not X.509 certificates
+        let Ok(certificate) = certificate.decode_as::<Certificate>() else {
+            continue;
+        };
+        if x509::public_key(&certificate).ok().as_ref() == Some(public_key) {
+            let tbs = &certificate.tbs_certificate;
+            key_identifiers.push(x509::key_identifier(&certificate)?);
+            issuers_and_serials.push((tbs.issuer.to_der()?, tbs.serial_number.to_der()?));
+        }
+    }
+    signed_data
+        .signer_infos
+        .iter()
+        .find(|signer| match &signer.sid {
+            SignerIdentifier::SubjectKeyIdentifier(id) => key_identifiers
+                .iter()
+                .any(|identifier| identifier.as_slice() == id.as_bytes()),
+            SignerIdentifier::IssuerAndSerialNumber(sid) => {
+                let sid = (sid.issuer.to_der().ok(), sid.serial_number.to_der().ok());
+                issuers_and_serials.iter().any(|(issuer, serial)| {
+                    sid.0.as_ref() == Some(issuer) && sid.1.as_ref() == Some(serial)
+                })
+            }
+        })
+        .ok_or_else(|| {
+            let ski: String = ski.iter().map(|b| format!("{:02x}", b)).collect();
+            CryptoError::KeyNotFound(format!(
+                "subject key identifier {} among the signers, or a certificate for the key",
+                ski
+            ))
+        })
+}
+
+/// Value of the signed attribute `oid`, which must be present exactly once with one value
+fn single_value<'a>(
+    attributes: &SetOfVec<Attribute<'a>>,
+    oid: &str,
+) -> Result<AnyRef<'a>, CryptoError> {
+    let oid = ObjectIdentifier::new_unwrap(oid);
+    let mut matching = attributes.iter().filter(|attr| attr.attr_type == oid);
+    match (matching.next(), matching.next()) {
+        (Some(attr), None) if attr.attr_values.len() == 1 => Ok(attr.attr_values.as_slice()[0]),
+        _ => Err(CryptoError::InvalidCms(format!(
+            "expected a single {} signed attribute",
+            oid
+        ))),
+    }
+}
+
+fn digest(
+    algorithm: &DigestAlgorithmIdentifier<'_>,
+    content: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    let oid = algorithm.algorithm;
+    if oid == ObjectIdentifier::new_unwrap(OID_SHA256) {
+        Ok(Sha256::digest(content).to_vec())
+    } else if oid == ObjectIdentifier::new_unwrap(OID_SHA384) {
+        Ok(Sha384::digest(content).to_vec())
+    } else if oid == ObjectIdentifier::new_unwrap(OID_SHA512) {
+        Ok(Sha512::digest(content).to_vec())
+    } else {
+        Err(CryptoError::UnsupportedOption(format!(
+            "digest algorithm {}",
+            oid
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asc1_dilithium::IssuerAndSerialNumber;
+    use der::asn1::{OctetString, UintRef};
+    use der::oid::AssociatedOid;
+    use std::fs;
+    use std::process::Command;
+    use std::time::Duration;
+    use x509_cert::ext::pkix::SubjectKeyIdentifier;
+
+    #[test]
+    fn attached_and_detached_round_trip() {
+        let secret = SecretKey::from_seed(Algorithm::Mldsa65, &[7u8; 32]);
+        let public = secret.public_key();
+        let attached = sign(&secret, b"content", &CmsSignOptions::default()).unwrap();
+        assert_eq!(
+            content(&attached).unwrap().as_deref(),
+            Some(&b"content"[..])
+        );
+        assert!(verify(&attached, &public, None).unwrap());
+        assert!(verify(&attached, &public, Some(b"content")).is_err());
+
+        let options = CmsSignOptions {
+            detached: true,
+            signing_time: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+            ..Default::default()
+        };
+        let detached = sign(&secret, b"content", &options).unwrap();
+        assert_eq!(content(&detached).unwrap(), None);
+        assert!(verify(&detached, &public, Some(b"content")).unwrap());
+        assert!(!verify(&detached, &public, Some(b"changed")).unwrap());
+        assert!(verify(&detached, &public, None).is_err());
+
+        let pem = to_pem(&detached);
+        assert!(pem.starts_with("-----BEGIN CMS-----"));
+        assert_eq!(decode(pem.as_bytes()).unwrap(), detached);
+    }
+
+    #[test]
+    fn other_key_does_not_verify() {
+        let secret = SecretKey::from_seed(Algorithm::Mldsa44, &[7u8; 32]);
+        let other = SecretKey::from_seed(Algorithm::Mldsa44, &[8u8; 32]).public_key();
+        let message = sign(&secret, b"content", &CmsSignOptions::default()).unwrap();
+        assert!(matches!(
+            verify(&message, &other, None),
+            Err(CryptoError::KeyNotFound(_))
+        ));
+
+        // Same key identifier, signature over other attributes
+        let public = secret.public_key();
+        let other_message = sign(&secret, b"other", &CmsSignOptions::default()).unwrap();
+        let other_info = ContentInfo::from_der(&other_message).unwrap();
+        let other_signature =
+            signed_data(&other_info).unwrap().signer_infos.as_slice()[0].signature;
+        let mut content_info = ContentInfo::from_der(&message).unwrap();
+        let mut forged_data = signed_data(&content_info).unwrap();
+        let mut signer = forged_data.signer_infos.as_slice()[0].clone();
+        signer.signature = other_signature;
+        forged_data.signer_infos = SetOfVec::try_from(vec![signer]).unwrap();
+        let forged_der = forged_data.to_der().unwrap();
+        content_info.content = AnyRef::from_der(&forged_der).unwrap();
+        let forged = content_info.to_der().unwrap();
+        assert!(!verify(&forged, &public, None).unwrap());
+    }
+
+    #[test]
+    fn signer_certificate_is_included() {
+        let secret = SecretKey::from_seed(Algorithm::Mldsa65, &[7u8; 32]);
+        let public = secret.public_key();
+        let name = x509::parse_name("CN=Signer").unwrap();
+        let mut certificate =
+            x509::self_signed(&secret, &x509::CertificateOptions::new(name, 30)).unwrap();
+        // A key identifier computed another way, as some CAs do
+        for extension in certificate.tbs_certificate.extensions.iter_mut().flatten() {
+            if extension.extn_id == SubjectKeyIdentifier::OID {
+                extension.extn_value = OctetString::new(
+                    SubjectKeyIdentifier(OctetString::new([9u8; 20]).unwrap())
+                        .to_der()
+                        .unwrap(),
+                )
+                .unwrap();
+            }
+        }
+        let options = CmsSignOptions {
+            certificate: Some(certificate.clone()),
+            ..Default::default()
+        };
+        let message = sign(&secret, b"content", &options).unwrap();
+        let content_info = ContentInfo::from_der(&message).unwrap();
+        let signed_data = signed_data(&content_info).unwrap();
+        let certificates = signed_data.certificates.as_ref().unwrap().as_slice();
+        assert_eq!(certificates.len(), 1);
+        assert_eq!(
+            certificates[0].decode_as::<Certificate>().unwrap(),
+            certificate
+        );
+        assert_eq!(
+            signed_data.signer_infos.as_slice()[0].sid,
+            SignerIdentifier::SubjectKeyIdentifier(OctetStringRef::new(&[9u8; 20]).unwrap())
+        );
+        assert!(verify(&message, &public, None).unwrap());
+
+        // Identified by issuer and serial number, as OpenSSL does without -keyid
+        let issuer = certificate.tbs_certificate.issuer.to_der().unwrap();
+        let serial = certificate.tbs_certificate.serial_number.to_der().unwrap();
+        let mut by_issuer = signed_data.clone();
+        let mut signer = by_issuer.signer_infos.as_slice()[0].clone();
+        signer.sid = SignerIdentifier::IssuerAndSerialNumber(IssuerAndSerialNumber {
+            issuer: AnyRef::from_der(&issuer).unwrap(),
+            serial_number: UintRef::from_der(&serial).unwrap(),
+        });
+        by_issuer.signer_infos = SetOfVec::try_from(vec![signer]).unwrap();
+        let by_issuer_der = by_issuer.to_der().unwrap();
+        let by_issuer = ContentInfo {
+            content: AnyRef::from_der(&by_issuer_der).unwrap(),
+            ..content_info.clone()
+        }
+        .to_der()
+        .unwrap();
+        assert!(verify(&by_issuer, &public, None).unwrap());
+        let other = SecretKey::from_seed(Algorithm::Mldsa65, &[8u8; 32]).public_key();
+        assert!(matches!(
+            verify(&by_issuer, &other, None),
+            Err(CryptoError::KeyNotFound(_))
+        ));
+
+        let other = SecretKey::from_seed(Algorithm::Mldsa65, &[8u8; 32]);
+        assert!(matches!(
+            sign(&other, b"content", &options),
+            Err(CryptoError::KeyMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn only_ml_dsa_keys_sign() {
+        for algorithm in [Algorithm::Dilithium3, Algorithm::Mldsa44Ed25519] {
+            let secret = SecretKey::from_seed(algorithm, &[7u8; 32]);
+            assert!(matches!(
+                sign(&secret, b"content", &CmsSignOptions::default()),
+                Err(CryptoError::InvalidAlgorithm(_))
+            ));
+        }
+    }
+
+    /// Both directions with `openssl cms`, which signs ML-DSA from OpenSSL 3.5 on. Run with
+    /// `cargo test -- --ignored`
+    #[test]
+    #[ignore = "needs OpenSSL 3.5 or later as openssl on PATH"]
+    fn openssl_interop() {
+        let dir = std::env::temp_dir().join(format!("crypto-cms-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let secret = SecretKey::from_seed(Algorithm::Mldsa65, &[7u8; 32]);
+        let name = x509::parse_name("CN=Signer").unwrap();
+        let certificate =
+            x509::self_signed(&secret, &x509::CertificateOptions::new(name, 30)).unwrap();
+        fs::write(dir.join("key.pem"), secret.to_pem().unwrap()).unwrap();
+        fs::write(dir.join("cert.pem"), x509::to_pem(&certificate).unwrap()).unwrap();
+        fs::write(dir.join("content"), b"content").unwrap();
+        let openssl = |args: &[&str]| {
+            let output = Command::new("openssl")
+                .current_dir(&dir)
+                .args(args)
+                .output()
+                .unwrap();
+            assert!(
+                output.status.success(),
+                "openssl {:?}: {}",
+                args,
+                String::from_utf8_lossy(&output.stderr)
+            );
+        };
+
+        let options = CmsSignOptions {
+            certificate: Some(certificate),
+            ..Default::default()
+        };
+        let ours = sign(&secret, b"content", &options).unwrap();
+        fs::write(dir.join("ours.pem"), to_pem(&ours)).unwrap();
+        openssl(&[
+            "cms", "-verify", "-in", "ours.pem", "-inform", "PEM", "-CAfile", "cert.pem",
+            "-purpose", "any", "-binary", "-out", "verified",
+        ]);
+        assert_eq!(fs::read(dir.join("verified")).unwrap(), b"content");
+
+        // By key identifier, by issuer and serial number of the included certificate, and by
+        // issuer and serial number with no certificate to resolve them
+        for (extra, found) in [
+            (Some("-keyid"), true),
+            (None, true),
+            (Some("-nocerts"), false),
+        ] {
+            let mut args = vec![
+                "cms",
+                "-sign",
+                "-in",
+                "content",
+                "-signer",
+                "cert.pem",
+                "-inkey",
+                "key.pem",
+                "-md",
+                "sha512",
+                "-nodetach",
+                "-binary",
+                "-outform",
+                "DER",
+                "-out",
+                "theirs.der",
+            ];
+            args.extend(extra);
+            openssl(&args);
+            let theirs = fs::read(dir.join("theirs.der")).unwrap();
+            match verify(&theirs, &secret.public_key(), None) {
+                Ok(valid) => assert!(found && valid),
+                Err(err) => assert!(!found && matches!(err, CryptoError::KeyNotFound(_))),
+            }
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,48 +1,97 @@
-use super::error::CryptoError;
+use crypto::CryptoError;
 use std::str::FromStr;
 
+/// Key and message encodings. The text encodings carry the same bytes as `Der`.
 #[derive(Debug, Copy, Clone, PartialEq)]
-pub enum Algorithm {
-    Dilithium2,
-    Dilithium3,
-    Dilithium5,
-    Mldsa44,
-    Mldsa65,
-    Mldsa87,
+pub enum Format {
+    Pem,
+    Der,
+    Hex,
+    /// Standard base64 alphabet with padding
+    Base64,
+    /// URL and filename safe base64 alphabet without padding
+    Base64Url,
+    /// Bitcoin base58 alphabet
+    Base58,
+}
+
+impl FromStr for Format {
+    type Err = CryptoError;
+
+    fn from_str(s: &str) -> Result<Self, CryptoError> {
+        match s.to_ascii_uppercase().as_str() {
+            "PEM" => Ok(Format::Pem),
+            "DER" => Ok(Format::Der),
+            "HEX" => Ok(Format::Hex),
+            "BASE64" => Ok(Format::Base64),
+            "BASE64URL" => Ok(Format::Base64Url),
+            "BASE58" => Ok(Format::Base58),
+            _ => Err(CryptoError::InvalidFormat(s.to_string())),
+        }
+    }
 }
 
-impl FromStr for Algorithm {
+/// Key derivation function for `--passout`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum KeyDerivation {
+    Pbkdf2,
+    Scrypt,
+}
+
+impl FromStr for KeyDerivation {
     type Err = CryptoError;
 
     fn from_str(s: &str) -> Result<Self, CryptoError> {
         match s.to_ascii_lowercase().as_str() {
-            "dilithium2" => Ok(Algorithm::Dilithium2),
-            "dil2" => Ok(Algorithm::Dilithium2),
-            "dilithium3" => Ok(Algorithm::Dilithium3),
-            "dil3" => Ok(Algorithm::Dilithium3),
-            "dilithium5" => Ok(Algorithm::Dilithium5),
-            "dil5" => Ok(Algorithm::Dilithium5),
-            "mldsa44" => Ok(Algorithm::Mldsa44),
-            "mldsa65" => Ok(Algorithm::Mldsa65),
-            "mldsa87" => Ok(Algorithm::Mldsa87),
-            _ => Err(CryptoError::InvalidAlgorithm(s.to_string())),
+            "pbkdf2" => Ok(KeyDerivation::Pbkdf2),
+            "scrypt" => Ok(KeyDerivation::Scrypt),
+            _ => Err(CryptoError::InvalidFormat(s.to_string())),
         }
     }
 }
 
+/// Signature output of `sign --outform`
 #[derive(Debug, Copy, Clone, PartialEq)]
-pub enum Format {
-    Pem,
+pub enum SignatureFormat {
+    /// Bare signature bytes
+    Raw,
+    /// DER `SignatureEnvelope`
     Der,
+    /// PEM `ML-DSA SIGNATURE`
+    Pem,
+    /// Bare signature bytes in a text encoding
+    Hex,
+    Base64,
+    Base64Url,
+    Base58,
 }
 
-impl FromStr for Format {
+impl SignatureFormat {
+    /// How bare signature bytes are written, `None` for an envelope
+    pub fn raw_encoding(self) -> Option<Format> {
+        match self {
+            SignatureFormat::Raw => Some(Format::Der),
+            SignatureFormat::Hex => Some(Format::Hex),
+            SignatureFormat::Base64 => Some(Format::Base64),
+            SignatureFormat::Base64Url => Some(Format::Base64Url),
+            SignatureFormat::Base58 => Some(Format::Base58),
+            SignatureFormat::Der | SignatureFormat::Pem => None,
+        }
+    }
+}
+
+impl FromStr for SignatureFormat {
     type Err = CryptoError;
 
     fn from_str(s: &str) -> Result<Self, CryptoError> {
-        match s {
-            "PEM" => Ok(Format::Pem),
-            "DER" => Ok(Format::Der),
+        match s.to_ascii_uppercase().as_str() {
+            "RAW" => Ok(SignatureFormat::Raw),
+            "DER" => Ok(SignatureFormat::Der),
+            "PEM" => Ok(SignatureFormat::Pem),
+            "HEX" => Ok(SignatureFormat::Hex),
+            "BASE64" => Ok(SignatureFormat::Base64),
+            "BASE64URL" => Ok(SignatureFormat::Base64Url),
+            "BASE58" => Ok(SignatureFormat::Base58),
             _ => Err(CryptoError::InvalidFormat(s.to_string())),
         }
     }
@@ -0,0 +1,390 @@
+use super::{arg_enums::Format, utils};
+use clap::{Args, Parser, Subcommand};
+use crypto::{
+    ca::{self, CertificateAuthority, Profile},
+    x509::{self, CertificateOptions},
+    CryptoError,
+};
+use der::{DateTime, Encode};
+use std::path::Path;
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, Parser)]
+#[clap(
+    name = "ca",
+    about = "Issue and revoke certificates and publish CRLs from a CA kept in a local directory"
+)]
+pub struct CaCmd {
+    #[clap(subcommand)]
+    pub action: CaAction,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum CaAction {
+    ///Create a CA directory with a self-signed CA certificate for a private key
+    Init(CaInitArgs),
+    ///Issue a certificate for a certificate signing request
+    Sign(CaSignArgs),
+    ///Mark an issued certificate as revoked
+    Revoke(CaRevokeArgs),
+    ///List the issued certificates
+    List(CaListArgs),
+    ///Issue a CRL of the revoked certificates
+    Crl(CaCrlArgs),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct CaInitArgs {
+    ///CA directory, created if needed
+    #[clap(long = "dir", value_name = "DIR")]
+    pub dir: String,
+    ///Input format of the private key (PEM, DER, HEX, BASE64, BASE64URL or BASE58), detected
+    ///from the file when omitted
+    #[clap(long = "inform", value_name = "FORMAT")]
+    pub inform: Option<Format>,
+    ///CA private key, copied into the directory as it is
+    #[clap(long = "sec", value_name = "FILE")]
+    pub in_path: String,
+    ///Password for an encrypted private key (pass:PASSWORD, env:VAR or file:PATH)
+    #[clap(long = "passin", value_name = "SOURCE")]
+    pub passin: Option<String>,
+    ///Subject distinguished name of the CA, e.g. "CN=Example Root CA,O=Example"
+    #[clap(long = "subject", value_name = "DN")]
+    pub subject: String,
+    ///Validity period of the CA certificate in days
+    #[clap(long = "days", value_name = "DAYS", default_value = "3650")]
+    pub days: u32,
+    ///Maximum number of intermediate CAs below this one
+    #[clap(long = "path-len", value_name = "N")]
+    pub path_len: Option<u8>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct CaSignArgs {
+    ///CA directory
+    #[clap(long = "dir", value_name = "DIR")]
+    pub dir: String,
+    ///Certificate signing request, PEM, DER, hex or base64
+    #[clap(long = "in", value_name = "FILE")]
+    pub request_path: String,
+    ///Certificate profile (server, client, code-signing or intermediate-ca)
+    #[clap(long = "profile", value_name = "PROFILE")]
+    pub profile: Profile,
+    ///Validity period in days, starting now
+    #[clap(long = "days", value_name = "DAYS", default_value = "365")]
+    pub days: u32,
+    ///Password for the CA private key when it is encrypted
+    #[clap(long = "passin", value_name = "SOURCE")]
+    pub passin: Option<String>,
+    ///Output file; the certificate is also kept in the CA directory
+    #[clap(long = "out", value_name = "FILE")]
+    pub out_path: Option<String>,
+    ///Output format (PEM, DER, HEX, BASE64, BASE64URL or BASE58)
+    #[clap(long = "outform", value_name = "FORMAT", default_value = "PEM")]
+    pub outform: Format,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct CaRevokeArgs {
+    ///CA directory
+    #[clap(long = "dir", value_name = "DIR")]
+    pub dir: String,
+    ///Serial number of the certificate in hex
+    #[clap(long = "serial", value_name = "HEX")]
+    pub serial: String,
+    ///Revocation reason (unspecified, keyCompromise, CACompromise, affiliationChanged,
+    ///superseded or cessationOfOperation)
+    #[clap(long = "reason", value_name = "REASON", default_value = "unspecified")]
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct CaListArgs {
+    ///CA directory
+    #[clap(long = "dir", value_name = "DIR")]
+    pub dir: String,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct CaCrlArgs {
+    ///CA directory
+    #[clap(long = "dir", value_name = "DIR")]
+    pub dir: String,
+    ///Days until the next CRL is due (nextUpdate)
+    #[clap(long = "days", value_name = "DAYS", default_value = "30")]
+    pub days: u32,
+    ///Password for the CA private key when it is encrypted
+    #[clap(long = "passin", value_name = "SOURCE")]
+    pub passin: Option<String>,
+    ///Output file
+    #[clap(long = "out", value_name = "FILE")]
+    pub out_path: Option<String>,
+    ///Output format (PEM, DER, HEX, BASE64, BASE64URL or BASE58)
+    #[clap(long = "outform", value_name = "FORMAT", default_value = "PEM")]
+    pub outform: Format,
+}
+
+impl CaCmd {
+    pub fn run(&self) -> Result<(), CryptoError> {
+        match &self.action {
+            CaAction::Init(args) => init(args),
+            CaAction::Sign(args) => sign(args),
+            CaAction::Revoke(args) => revoke(args),
+            CaAction::List(args) => list(args),
+            CaAction::Crl(args) => crl(args),
+        }
+    }
+}
+
+fn init(args: &CaInitArgs) -> Result<(), CryptoError> {
+    let key_file = utils::read_file(&args.in_path)?;
+    let secret_key =
+        utils::decode_secret_key(key_file.clone(), &args.in_path, args.inform, &args.passin)?;
+    let mut options = CertificateOptions::new(x509::parse_name(&args.subject)?, args.days);
+    options.extensions.ca = true;
+    options.extensions.path_len = args.path_len;
+    let certificate = x509::self_signed(&secret_key, &options)?;
+    CertificateAuthority::create(Path::new(&args.dir), &certificate, &key_file)?;
+    utils::print_fingerprint(&secret_key.public_key())
+}
+
+fn sign(args: &CaSignArgs) -> Result<(), CryptoError> {
+    let ca = CertificateAuthority::open(Path::new(&args.dir))?;
+    let key_path = ca.key_path().display().to_string();
+    let ca_key = utils::read_secret_key(&key_path, None, &args.passin)?;
+    let request = utils::decode_text(utils::read_file(&args.request_path)?, None)
+        .and_then(|bytes| x509::decode_request(&bytes))
+        .map_err(|err| err.in_file(&args.request_path))?;
+    let certificate = ca
+        .issue(&ca_key, &request, args.profile, args.days)
+        .map_err(|err| err.in_file(&args.request_path))?;
+
+    if args.outform == Format::Pem {
+        let pem = x509::to_pem(&certificate)?;
+        utils::output(pem.as_bytes(), &args.out_path, Format::Pem)?;
+    } else {
+        utils::output(&certificate.to_der()?, &args.out_path, args.outform)?;
+    }
+    let serial_number = certificate.tbs_certificate.serial_number.as_bytes();
+    eprintln!("Serial: {}", ca::encode_serial(serial_number));
+    eprintln!("Subject: {}", certificate.tbs_certificate.subject);
+    Ok(())
+}
+
+fn revoke(args: &CaRevokeArgs) -> Result<(), CryptoError> {
+    let ca = CertificateAuthority::open(Path::new(&args.dir))?;
+    let serial_number = utils::decode_hex(&args.serial)
+        .filter(|bytes| !bytes.is_empty())
+        .ok_or_else(|| {
+            CryptoError::InvalidArgument(format!("serial {} is not valid hex", args.serial))
+        })?;
+    let reason = ca::parse_reason(&args.reason)?;
+    let entry = ca.revoke(&serial_number, reason, SystemTime::now())?;
+    eprintln!(
+        "Revoked: {} {}",
+        ca::encode_serial(&entry.serial_number),
+        entry.subject
+    );
+    Ok(())
+}
+
+fn list(args: &CaListArgs) -> Result<(), CryptoError> {
+    let ca = CertificateAuthority::open(Path::new(&args.dir))?;
+    for entry in ca.entries()? {
+        let status = match entry.revocation {
+            None => "valid".to_string(),
+            Some(revocation) => format!(
+                "revoked {} {}",
+                DateTime::from_system_time(revocation.time)?,
+                ca::reason_name(revocation.reason)
+            ),
+        };
+        println!(
+            "{} expires {} {} {}",
+            ca::encode_serial(&entry.serial_number),
+            DateTime::from_system_time(entry.not_after)?,
+            status,
+            entry.subject
+        );
+    }
+    Ok(())
+}
+
+fn crl(args: &CaCrlArgs) -> Result<(), CryptoError> {
+    let ca = CertificateAuthority::open(Path::new(&args.dir))?;
+    let key_path = ca.key_path().display().to_string();
+    let ca_key = utils::read_secret_key(&key_path, None, &args.passin)?;
+    let crl = ca.crl(&ca_key, args.days)?;
+
+    if args.outform == Format::Pem {
+        let pem = x509::crl_to_pem(&crl)?;
+        utils::output(pem.as_bytes(), &args.out_path, Format::Pem)?;
+    } else {
+        utils::output(&crl.to_der()?, &args.out_path, args.outform)?;
+    }
+    let revoked = crl.tbs_cert_list.revoked_certificates.as_ref();
+    eprintln!("Revoked certificates: {}", revoked.map_or(0, Vec::len));
+    if let Some(next_update) = crl.tbs_cert_list.next_update {
+        eprintln!("Next update: {}", next_update);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::commands::{CertCmd, GenerateCmd, ReqCmd};
+    use std::fs;
+
+    #[test]
+    fn ca_issues_and_revokes() {
+        let dir = "ca_dir_test".to_string();
+        let ca_sec_file = "ca_ca_sec_test".to_string();
+        let sec_file = "ca_sec_test".to_string();
+        let req_file = "ca_req_test".to_string();
+        let cert_file = "ca_cert_test".to_string();
+        let crl_file = "ca_crl_test".to_string();
+        let _ = fs::remove_dir_all(&dir);
+        for (file, algorithm) in [(&ca_sec_file, "mldsa87"), (&sec_file, "mldsa44")] {
+            GenerateCmd::parse_from([
+                "generate",
+                "--algorithm",
+                algorithm,
+                "--out",
+                file,
+                "--passout",
+                "pass:secret",
+            ])
+            .run()
+            .unwrap();
+        }
+        CaCmd::parse_from([
+            "ca",
+            "init",
+            "--dir",
+            &dir,
+            "--sec",
+            &ca_sec_file,
+            "--passin",
+            "pass:secret",
+            "--subject",
+            "CN=Example CA",
+        ])
+        .run()
+        .unwrap();
+        // The key is kept encrypted
+        assert_eq!(
+            fs::read(Path::new(&dir).join(ca::KEY_FILE)).unwrap(),
+            fs::read(&ca_sec_file).unwrap()
+        );
+        ReqCmd::parse_from([
+            "req",
+            "--sec",
+            &sec_file,
+            "--passin",
+            "pass:secret",
+            "--subject",
+            "CN=build",
+            "--out",
+            &req_file,
+        ])
+        .run()
+        .unwrap();
+
+        let sign = [
+            "ca",
+            "sign",
+            "--dir",
+            &dir,
+            "--in",
+            &req_file,
+            "--profile",
+            "code-signing",
+            "--out",
+            &cert_file,
+        ];
+        assert!(matches!(
+            CaCmd::parse_from(sign).run(),
+            Err(CryptoError::File { source, .. }) if matches!(*source, CryptoError::MissingPassword)
+        ));
+        let mut with_password = sign.to_vec();
+        with_password.extend(["--passin", "pass:secret"]);
+        CaCmd::parse_from(&with_password).run().unwrap();
+
+        let certificate = x509::decode(&fs::read(&cert_file).unwrap()).unwrap();
+        let ca = CertificateAuthority::open(Path::new(&dir)).unwrap();
+        let ca_key = x509::public_key(ca.certificate()).unwrap();
+        assert!(x509::verify_signature(&certificate, &ca_key).unwrap());
+        let serial = ca::encode_serial(certificate.tbs_certificate.serial_number.as_bytes());
+
+        CaCmd::parse_from([
+            "ca",
+            "revoke",
+            "--dir",
+            &dir,
+            "--serial",
+            &serial,
+            "--reason",
+            "superseded",
+        ])
+        .run()
+        .unwrap();
+        let revoked = ca.entries().unwrap()[0].revocation.unwrap();
+        assert_eq!(ca::reason_name(revoked.reason), "superseded");
+        CaCmd::parse_from([
+            "ca",
+            "crl",
+            "--dir",
+            &dir,
+            "--passin",
+            "pass:secret",
+            "--out",
+            &crl_file,
+        ])
+        .run()
+        .unwrap();
+        let ca_file = Path::new(&dir).join(ca::CERTIFICATE_FILE);
+        let ca_file = ca_file.to_str().unwrap();
+        let check = |crl: &str| {
+            CertCmd::parse_from([
+                "cert",
+                "--verify",
+                "--in",
+                &cert_file,
+                "--trusted",
+                ca_file,
+                "--crl",
+                crl,
+            ])
+            .run()
+        };
+        assert!(matches!(
+            check(&crl_file),
+            Err(CryptoError::Revoked { reason, .. }) if reason == "superseded"
+        ));
+        assert!(matches!(check(&cert_file), Err(CryptoError::File { .. })));
+        CaCmd::parse_from(["ca", "list", "--dir", &dir])
+            .run()
+            .unwrap();
+
+        assert!(CaCmd::try_parse_from([
+            "ca",
+            "sign",
+            "--dir",
+            &dir,
+            "--in",
+            &req_file,
+            "--profile",
+            "email"
+        ])
+        .is_err());
+        assert!(matches!(
+            CaCmd::parse_from(["ca", "list", "--dir", "ca_missing_test"]).run(),
+            Err(CryptoError::CertificateAuthority(_))
+        ));
+        let _ = fs::remove_dir_all(dir);
+        for file in [ca_sec_file, sec_file, req_file, cert_file, crl_file] {
+            let _ = fs::remove_file(file);
+        }
+    }
+}
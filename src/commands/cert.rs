@@ -0,0 +1,348 @@
+use super::{arg_enums::Format, utils};
+use clap::{Args, Parser};
+use crypto::{
+    chain::{self, ValidationOptions},
+    x509::{self, Certificate, CertificateOptions, ExtensionOptions},
+    CryptoError,
+};
+use der::Encode;
+
+#[derive(Debug, Clone, Parser)]
+#[clap(
+    name = "cert",
+    about = "Create a self-signed X.509 certificate, or validate a certificate path with --verify"
+)]
+pub struct CertCmd {
+    ///Input format (PEM, DER, HEX, BASE64, BASE64URL or BASE58), detected from the file
+    ///when omitted
+    #[clap(long = "inform", value_name = "FORMAT")]
+    pub inform: Option<Format>,
+    ///Private key file, or a directory of keys to choose from with --key-id
+    #[clap(
+        long = "sec",
+        value_name = "FILE|DIR",
+        required_unless_present = "verify",
+        conflicts_with = "verify"
+    )]
+    pub in_path: Option<String>,
+    ///Use the key with this key ID or fingerprint
+    #[clap(long = "key-id", value_name = "ID")]
+    pub key_id: Option<String>,
+    ///Password for an encrypted private key (pass:PASSWORD, env:VAR or file:PATH)
+    #[clap(long = "passin", value_name = "SOURCE")]
+    pub passin: Option<String>,
+    ///Subject distinguished name, e.g. "CN=service,O=Example"
+    #[clap(
+        long = "subject",
+        value_name = "DN",
+        required_unless_present = "verify",
+        conflicts_with = "verify"
+    )]
+    pub subject: Option<String>,
+    ///Validity period in days, starting at --not-before
+    #[clap(long = "days", value_name = "DAYS", default_value = "365")]
+    pub days: u32,
+    ///Start of the validity period (YYYY-MM-DDTHH:MM:SSZ), now when omitted
+    #[clap(long = "not-before", value_name = "TIME")]
+    pub not_before: Option<String>,
+    ///End of the validity period (YYYY-MM-DDTHH:MM:SSZ), overrides --days
+    #[clap(long = "not-after", value_name = "TIME")]
+    pub not_after: Option<String>,
+    ///Serial number in hex, random when omitted
+    #[clap(long = "serial", value_name = "HEX")]
+    pub serial: Option<String>,
+    #[clap(flatten)]
+    pub extensions: ExtensionArgs,
+    ///Output file
+    #[clap(long = "out", value_name = "FILE")]
+    pub out_path: Option<String>,
+    ///Output format (PEM, DER, HEX, BASE64, BASE64URL or BASE58)
+    #[clap(long = "outform", value_name = "FORMAT", default_value = "PEM")]
+    pub outform: Format,
+    ///Validate the path from the certificate given with --in to a --trusted certificate
+    #[clap(long = "verify", requires = "cert_path")]
+    pub verify: bool,
+    ///Certificate to validate, PEM, DER, hex or base64
+    #[clap(
+        long = "in",
+        value_name = "FILE",
+        requires = "verify",
+        requires = "trusted"
+    )]
+    pub cert_path: Option<String>,
+    #[clap(flatten)]
+    pub chain: ChainArgs,
+}
+
+/// Trust anchors, intermediates, CRLs and validation time, shared with `verify --cert`
+#[derive(Debug, Clone, Args)]
+pub struct ChainArgs {
+    ///Trust anchor certificates, a PEM bundle or one DER certificate per file, repeatable
+    #[clap(long = "trusted", value_name = "FILE", requires = "cert_path")]
+    pub trusted: Vec<String>,
+    ///Intermediate CA certificates, a PEM bundle or one DER certificate per file, repeatable
+    #[clap(long = "intermediates", value_name = "FILE", requires = "trusted")]
+    pub intermediates: Vec<String>,
+    ///CRL to check the certificates of the path against, PEM or DER, repeatable
+    #[clap(long = "crl", value_name = "FILE", requires = "trusted")]
+    pub crls: Vec<String>,
+    ///Time to validate the path at (YYYY-MM-DDTHH:MM:SSZ), now when omitted
+    #[clap(long = "at", value_name = "TIME", requires = "trusted")]
+    pub at: Option<String>,
+}
+
+impl ChainArgs {
+    /// Path from `certificate` to a trust anchor, leaf first
+    pub fn validate(&self, certificate: &Certificate) -> Result<Vec<Certificate>, CryptoError> {
+        let anchors = utils::read_certificates(&self.trusted)?;
+        let intermediates = utils::read_certificates(&self.intermediates)?;
+        let mut options = ValidationOptions {
+            crls: utils::read_crls(&self.crls)?,
+            ..Default::default()
+        };
+        if let Some(at) = &self.at {
+            options.time = x509::parse_time(at)?;
+        }
+        chain::validate(certificate, &intermediates, &anchors, &options)
+    }
+}
+
+/// basicConstraints, keyUsage and subjectAltName, shared with `req`
+#[derive(Debug, Clone, Args)]
+pub struct ExtensionArgs {
+    ///Mark the key as a CA (basicConstraints cA)
+    #[clap(long = "ca")]
+    pub ca: bool,
+    ///Maximum number of intermediate CAs below this one
+    #[clap(long = "path-len", value_name = "N", requires = "ca")]
+    pub path_len: Option<u8>,
+    ///Comma separated key usages (digitalSignature, nonRepudiation, keyCertSign, cRLSign);
+    ///digitalSignature, plus keyCertSign and cRLSign with --ca, when omitted
+    #[clap(long = "key-usage", value_name = "USAGES")]
+    pub key_usage: Option<String>,
+    ///Subject alternative name (DNS:NAME, IP:ADDRESS, email:ADDRESS or URI:URI), repeatable
+    #[clap(long = "san", value_name = "NAME")]
+    pub san: Vec<String>,
+}
+
+impl ExtensionArgs {
+    pub fn to_options(&self) -> Result<ExtensionOptions, CryptoError> {
+        Ok(ExtensionOptions {
+            ca: self.ca,
+            path_len: self.path_len,
+            key_usage: self
+                .key_usage
+                .as_deref()
+                .map(x509::parse_key_usage)
+                .transpose()?,
+            subject_alt_names: self
+                .san
+                .iter()
+                .map(|name| x509::parse_general_name(name))
+                .collect::<Result<_, _>>()?,
+            extended_key_usage: Vec::new(),
+        })
+    }
+}
+
+impl CertCmd {
+    pub fn run(&self) -> Result<(), CryptoError> {
+        match (&self.cert_path, &self.in_path, &self.subject) {
+            (Some(cert_path), _, _) => self.verify(cert_path),
+            (None, Some(in_path), Some(subject)) => self.create(in_path, subject),
+            _ => unreachable!("clap requires --sec and --subject without --verify"),
+        }
+    }
+
+    fn create(&self, in_path: &String, subject: &str) -> Result<(), CryptoError> {
+        let secret_key =
+            utils::select_secret_key(in_path, self.inform, &self.passin, &self.key_id)?;
+        let mut options = CertificateOptions::new(x509::parse_name(subject)?, self.days);
+        if let Some(not_before) = &self.not_before {
+            let days = options
+                .not_after
+                .duration_since(options.not_before)
+                .unwrap_or_default();
+            options.not_before = x509::parse_time(not_before)?;
+            options.not_after = options.not_before + days;
+        }
+        if let Some(not_after) = &self.not_after {
+            options.not_after = x509::parse_time(not_after)?;
+        }
+        if options.not_after <= options.not_before {
+            return Err(CryptoError::InvalidArgument(
+                "the validity period ends before it starts".to_string(),
+            ));
+        }
+        options.serial_number = self
+            .serial
+            .as_ref()
+            .map(|serial| {
+                utils::decode_hex(serial)
+                    .filter(|bytes| !bytes.is_empty())
+                    .ok_or_else(|| {
+                        CryptoError::InvalidArgument(format!("serial {} is not valid hex", serial))
+                    })
+            })
+            .transpose()?;
+        options.extensions = self.extensions.to_options()?;
+
+        let certificate = x509::self_signed(&secret_key, &options)?;
+        if self.outform == Format::Pem {
+            let pem = x509::to_pem(&certificate)?;
+            utils::output(pem.as_bytes(), &self.out_path, Format::Pem)?;
+        } else {
+            utils::output(&certificate.to_der()?, &self.out_path, self.outform)?;
+        }
+        utils::print_fingerprint(&secret_key.public_key())
+    }
+
+    fn verify(&self, cert_path: &String) -> Result<(), CryptoError> {
+        let certificate = utils::read_certificate(cert_path)?;
+        match self.chain.validate(&certificate) {
+            Ok(path) => {
+                let (anchor, certificates) = path.split_last().expect("a path ends with an anchor");
+                for certificate in certificates {
+                    println!("Certificate: {}", certificate.tbs_certificate.subject);
+                }
+                println!("Trust anchor: {}", anchor.tbs_certificate.subject);
+                println!("Verification: true");
+                Ok(())
+            }
+            Err(err @ (CryptoError::PathValidation { .. } | CryptoError::Revoked { .. })) => {
+                println!("Verification: false");
+                Err(err)
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::commands::GenerateCmd;
+    use std::fs;
+
+    #[test]
+    fn cert_is_self_signed() {
+        let sec_file = "cert_sec_test".to_string();
+        let cert_file = "cert_cert_test".to_string();
+        GenerateCmd::parse_from(["generate", "--algorithm", "mldsa44", "--out", &sec_file])
+            .run()
+            .unwrap();
+        CertCmd::parse_from([
+            "cert",
+            "--sec",
+            &sec_file,
+            "--subject",
+            "CN=test,O=Example",
+            "--not-before",
+            "2025-01-01T00:00:00Z",
+            "--days",
+            "10",
+            "--serial",
+            "0102",
+            "--san",
+            "DNS:test.example",
+            "--out",
+            &cert_file,
+        ])
+        .run()
+        .unwrap();
+
+        let certificate = x509::decode(&fs::read(&cert_file).unwrap()).unwrap();
+        let tbs = &certificate.tbs_certificate;
+        assert_eq!(tbs.serial_number.as_bytes(), [1, 2]);
+        assert_eq!(tbs.validity.not_after.to_string(), "2025-01-11T00:00:00Z");
+        let public_key = x509::public_key(&certificate).unwrap();
+        assert!(x509::verify_signature(&certificate, &public_key).unwrap());
+
+        let bad_range = CertCmd::parse_from([
+            "cert",
+            "--sec",
+            &sec_file,
+            "--subject",
+            "CN=test",
+            "--not-before",
+            "2025-01-01T00:00:00Z",
+            "--not-after",
+            "2024-01-01T00:00:00Z",
+        ]);
+        assert!(matches!(
+            bad_range.run(),
+            Err(CryptoError::InvalidArgument(_))
+        ));
+        let _ = fs::remove_file(sec_file);
+        let _ = fs::remove_file(cert_file);
+    }
+
+    #[test]
+    fn cert_verify_validates_the_path() {
+        let root_file = "cert_root_test".to_string();
+        let leaf_file = "cert_leaf_test".to_string();
+        let root_key = crypto::generate(crypto::Algorithm::Mldsa87);
+        let mut options = CertificateOptions::new(x509::parse_name("CN=Root").unwrap(), 30);
+        options.extensions.ca = true;
+        let root = x509::self_signed(&root_key, &options).unwrap();
+        let leaf_key = crypto::generate(crypto::Algorithm::Mldsa44);
+        let leaf = x509::issue(
+            &root_key,
+            &root,
+            &leaf_key.public_key(),
+            &CertificateOptions::new(x509::parse_name("CN=Leaf").unwrap(), 10),
+        )
+        .unwrap();
+        fs::write(&root_file, x509::to_pem(&root).unwrap()).unwrap();
+        fs::write(&leaf_file, leaf.to_der().unwrap()).unwrap();
+
+        let verify = |extra: &[&str]| {
+            let mut args = vec![
+                "cert",
+                "--verify",
+                "--in",
+                &leaf_file,
+                "--trusted",
+                &root_file,
+            ];
+            args.extend_from_slice(extra);
+            CertCmd::parse_from(args).run()
+        };
+        assert!(verify(&[]).is_ok());
+        assert!(matches!(
+            verify(&["--at", "2000-01-01T00:00:00Z"]),
+            Err(CryptoError::PathValidation {
+                check: chain::PathCheck::NotYetValid(_),
+                ..
+            })
+        ));
+        let untrusted = CertCmd::parse_from([
+            "cert",
+            "--verify",
+            "--in",
+            &leaf_file,
+            "--trusted",
+            &leaf_file.replace("leaf", "missing"),
+        ]);
+        assert!(matches!(untrusted.run(), Err(CryptoError::Io(_))));
+        let self_anchored = CertCmd::parse_from([
+            "cert",
+            "--verify",
+            "--in",
+            &root_file,
+            "--trusted",
+            &leaf_file,
+        ]);
+        assert!(matches!(
+            self_anchored.run(),
+            Err(CryptoError::PathValidation {
+                check: chain::PathCheck::NoIssuer(_),
+                ..
+            })
+        ));
+        assert!(CertCmd::try_parse_from(["cert", "--verify", "--in", &leaf_file]).is_err());
+        assert!(CertCmd::try_parse_from(["cert", "--trusted", &root_file]).is_err());
+        let _ = fs::remove_file(root_file);
+        let _ = fs::remove_file(leaf_file);
+    }
+}
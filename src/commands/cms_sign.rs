@@ -0,0 +1,75 @@
+use super::{arg_enums::Format, utils};
+use clap::Parser;
+use crypto::{
+    cms::{self, CmsSignOptions},
+    CryptoError,
+};
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, Parser)]
+#[clap(name = "cms-sign", about = "Sign the file as CMS SignedData")]
+pub struct CmsSignCmd {
+    ///Input format (PEM, DER, HEX, BASE64, BASE64URL or BASE58), detected from the file
+    ///when omitted
+    #[clap(long = "inform", value_name = "FORMAT")]
+    pub inform: Option<Format>,
+    ///Private key file, or a directory of keys to choose from with --key-id
+    #[clap(long = "sec", value_name = "FILE|DIR")]
+    in_path: String,
+    ///Use the key with this key ID or fingerprint
+    #[clap(long = "key-id", value_name = "ID")]
+    key_id: Option<String>,
+    ///Password for an encrypted private key (pass:PASSWORD, env:VAR or file:PATH)
+    #[clap(long = "passin", value_name = "SOURCE")]
+    passin: Option<String>,
+    ///Signer certificate to include in the message, for the private key
+    #[clap(long = "cert", value_name = "FILE")]
+    cert_path: Option<String>,
+    ///Input file for signing
+    #[clap(long = "file", value_name = "FILE")]
+    file_path: String,
+    ///Output file
+    #[clap(long = "out", value_name = "FILE")]
+    out_path: Option<String>,
+    ///Output format (PEM, DER, HEX, BASE64, BASE64URL or BASE58)
+    #[clap(long = "outform", value_name = "FORMAT", default_value = "PEM")]
+    outform: Format,
+    ///Leave the file out of the CMS message
+    #[clap(long = "detached")]
+    detached: bool,
+    ///Add the current time as a signingTime attribute
+    #[clap(long = "signing-time")]
+    signing_time: bool,
+}
+
+impl CmsSignCmd {
+    pub fn run(&self) -> Result<(), CryptoError> {
+        utils::check_stdin([
+            Some(&self.in_path),
+            self.cert_path.as_ref(),
+            Some(&self.file_path),
+        ])?;
+        let secret_key =
+            utils::select_secret_key(&self.in_path, self.inform, &self.passin, &self.key_id)?;
+        let content = utils::read_file(&self.file_path)?;
+        let options = CmsSignOptions {
+            detached: self.detached,
+            signing_time: self.signing_time.then(SystemTime::now),
+            certificate: self
+                .cert_path
+                .as_ref()
+                .map(utils::read_certificate)
+                .transpose()?,
+        };
+        let message = cms::sign(&secret_key, &content, &options)?;
+        if self.outform == Format::Pem {
+            utils::output(
+                cms::to_pem(&message).as_bytes(),
+                &self.out_path,
+                Format::Pem,
+            )
+        } else {
+            utils::output(&message, &self.out_path, self.outform)
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use super::{arg_enums::Format, utils};
+use clap::Parser;
+use crypto::{cms, CryptoError};
+
+#[derive(Debug, Clone, Parser)]
+#[clap(name = "cms-verify", about = "Verify a CMS SignedData message")]
+pub struct CmsVerifyCmd {
+    ///Input format of the public key (PEM, DER, HEX, BASE64, BASE64URL or BASE58), detected from the file
+    ///when omitted
+    #[clap(long = "inform", value_name = "FORMAT")]
+    pub inform: Option<Format>,
+    ///Public key file
+    #[clap(long = "pub", value_name = "FILE")]
+    pub pub_path: String,
+    ///CMS message, PEM, DER, hex or base64
+    #[clap(long = "in", value_name = "FILE")]
+    pub in_path: String,
+    ///Signed file, for a detached signature
+    #[clap(long = "content", value_name = "FILE")]
+    pub content_path: Option<String>,
+    ///Write the encapsulated content to this file once verified
+    #[clap(long = "out", value_name = "FILE")]
+    pub out_path: Option<String>,
+    ///Print only the verification result (true or false)
+    #[clap(long = "brief")]
+    pub brief: bool,
+}
+
+impl CmsVerifyCmd {
+    pub fn run(&self) -> Result<(), CryptoError> {
+        utils::check_stdin([
+            Some(&self.pub_path),
+            Some(&self.in_path),
+            self.content_path.as_ref(),
+        ])?;
+        let public_key = utils::read_public_key(&self.pub_path, self.inform)?;
+        let message = utils::decode_text(utils::read_file(&self.in_path)?, None)
+            .and_then(|bytes| cms::decode(&bytes))
+            .map_err(|err| err.in_file(&self.in_path))?;
+        let detached = self
+            .content_path
+            .as_ref()
+            .map(utils::read_file)
+            .transpose()?;
+
+        let ver = cms::verify(&message, &public_key, detached.as_deref())
+            .map_err(|err| err.in_file(&self.in_path))?;
+        // With the content going to stdout, the report goes to stderr
+        let report = |line: String| {
+            if self.out_path.as_deref() == Some(utils::STDIO) {
+                eprintln!("{}", line);
+            } else {
+                println!("{}", line);
+            }
+        };
+        if self.brief {
+            report(format!("{:?}", ver));
+        } else {
+            let fingerprint = public_key.fingerprint()?;
+            report(format!("Fingerprint: {}", fingerprint));
+            report(format!("Key ID: {}", fingerprint.key_id()));
+            report(format!("Verification: {:?}", ver));
+        }
+        if !ver {
+            return Err(CryptoError::VerificationFailed);
+        }
+        if let (Some(out_path), Some(content)) = (&self.out_path, cms::content(&message)?) {
+            utils::write_file(out_path, &content)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::commands::{CmsSignCmd, GenerateCmd, PublicCmd};
+    use std::fs;
+
+    fn cleanup(files: &[String]) {
+        for f in files {
+            let _ = fs::remove_file(f);
+        }
+    }
+
+    #[test]
+    fn cms_sign_and_verify() {
+        let sec_file = "cms_sec_test".to_string();
+        let pub_file = "cms_pub_test".to_string();
+        let data_file = "cms_data_test".to_string();
+        let out_file = "cms_out_test".to_string();
+        fs::write(&data_file, b"signed content").unwrap();
+        GenerateCmd::parse_from(["generate", "--algorithm", "mldsa65", "--out", &sec_file])
+            .run()
+            .unwrap();
+        PublicCmd::parse_from(["public", "--in", &sec_file, "--out", &pub_file])
+            .run()
+            .unwrap();
+
+        for (outform, detached) in [("PEM", false), ("DER", true)] {
+            let cms_file = format!("cms_msg_test_{}", outform.to_lowercase());
+            let mut args = vec![
+                "cms-sign",
+                "--sec",
+                &sec_file,
+                "--file",
+                &data_file,
+                "--out",
+                &cms_file,
+                "--outform",
+                outform,
+            ];
+            if detached {
+                args.push("--detached");
+            }
+            CmsSignCmd::parse_from(args).run().unwrap();
+
+            let mut args = vec![
+                "cms-verify",
+                "--pub",
+                &pub_file,
+                "--in",
+                &cms_file,
+                "--out",
+                &out_file,
+            ];
+            if detached {
+                args.extend(["--content", &data_file]);
+            }
+            let verify = CmsVerifyCmd::parse_from(&args);
+            assert!(verify.run().is_ok());
+            if !detached {
+                assert_eq!(fs::read(&out_file).unwrap(), b"signed content");
+            } else {
+                let verify = CmsVerifyCmd::parse_from([
+                    "cms-verify",
+                    "--pub",
+                    &pub_file,
+                    "--in",
+                    &cms_file,
+                    "--content",
+                    &sec_file,
+                ]);
+                assert!(matches!(verify.run(), Err(CryptoError::VerificationFailed)));
+            }
+            cleanup(&[cms_file]);
+        }
+        cleanup(&[sec_file, pub_file, data_file, out_file]);
+    }
+}
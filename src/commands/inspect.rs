@@ -0,0 +1,420 @@
+use super::utils;
+use clap::Parser;
+use crypto::asc1_dilithium::{
+    MlDsaPrivateKey, OneAsymmetricKeyBorrowed, SignatureEnvelopeBorrowed,
+    SubjectPublicKeyInfoBorrowed,
+};
+use crypto::x509::{self, CertReq, Certificate};
+use crypto::{
+    armor, Algorithm, CryptoError, Fingerprint, PublicKey, SecretKey, SignatureEnvelope, ALGORITHMS,
+};
+use der::pem::PemLabel;
+use der::Decode;
+use pkcs8::pkcs5::{pbes2, EncryptionScheme};
+use pkcs8::EncryptedPrivateKeyInfo;
+
+#[derive(Debug, Clone, Parser)]
+#[clap(name = "inspect", about = "Describe a key or signature file")]
+pub struct InspectCmd {
+    #[clap(short = 'i', long = "in", value_name = "FILE")]
+    pub in_path: String,
+    ///Password for an encrypted private key (pass:PASSWORD, env:VAR or file:PATH)
+    #[clap(long = "passin", value_name = "SOURCE")]
+    pub passin: Option<String>,
+    ///Print the report as JSON
+    #[clap(long = "json")]
+    pub json: bool,
+}
+
+/// What `inspect` found in a file
+#[derive(Debug, Default, PartialEq)]
+struct Report {
+    kind: &'static str,
+    /// `PEM`, `DER` or `raw`
+    encoding: &'static str,
+    pem_label: Option<String>,
+    /// Label the structure should be armored with
+    expected_label: Option<&'static str>,
+    /// More than one entry only for a raw signature whose length several parameter sets share
+    algorithms: Vec<Algorithm>,
+    /// `OneAsymmetricKey` version and private key CHOICE
+    key_version: Option<u8>,
+    private_key_format: Option<&'static str>,
+    encryption: Option<String>,
+    /// Certificate subject distinguished name
+    subject: Option<String>,
+    fingerprint: Option<Fingerprint>,
+}
+
+impl InspectCmd {
+    pub fn run(&self) -> Result<(), CryptoError> {
+        // Hex or base64 text is described as the DER or signature it carries
+        let bytes = utils::decode_text(utils::read_file(&self.in_path)?, None)?;
+        let password = self
+            .passin
+            .as_deref()
+            .map(utils::read_password)
+            .transpose()?;
+        let report =
+            inspect(&bytes, password.as_deref()).map_err(|err| err.in_file(&self.in_path))?;
+        if self.json {
+            println!("{}", report.to_json());
+        } else {
+            print!("{}", report.to_text());
+        }
+        Ok(())
+    }
+}
+
+fn inspect(bytes: &[u8], password: Option<&[u8]>) -> Result<Report, CryptoError> {
+    if !bytes.trim_ascii_start().starts_with(b"-----BEGIN ") {
+        return inspect_der(bytes, password).or_else(|err| inspect_signature(bytes).ok_or(err));
+    }
+    let (label, der) = armor::decode(bytes)?;
+    Ok(Report {
+        encoding: "PEM",
+        pem_label: Some(label),
+        ..inspect_der(&der, password)?
+    })
+}
+
+fn inspect_der(der: &[u8], password: Option<&[u8]>) -> Result<Report, CryptoError> {
+    if let Ok(spki) = SubjectPublicKeyInfoBorrowed::from_der(der) {
+        let algorithm = Algorithm::from_oid(&spki.algorithm.algorithm)?;
+        let public_key = PublicKey::from_der(der)?;
+        return Ok(Report {
+            kind: "public key",
+            encoding: "DER",
+            expected_label: Some(SubjectPublicKeyInfoBorrowed::PEM_LABEL),
+            algorithms: vec![algorithm],
+            fingerprint: Some(public_key.fingerprint()?),
+            ..Default::default()
+        });
+    }
+    if let Ok(certificate) = Certificate::from_der(der) {
+        let public_key = x509::public_key(&certificate)?;
+        return Ok(Report {
+            kind: "certificate",
+            encoding: "DER",
+            expected_label: Some(Certificate::PEM_LABEL),
+            algorithms: vec![public_key.algorithm()],
+            subject: Some(certificate.tbs_certificate.subject.to_string()),
+            fingerprint: Some(public_key.fingerprint()?),
+            ..Default::default()
+        });
+    }
+    if let Ok(request) = CertReq::from_der(der) {
+        let public_key = x509::request_public_key(&request)?;
+        return Ok(Report {
+            kind: "certificate request",
+            encoding: "DER",
+            expected_label: Some(CertReq::PEM_LABEL),
+            algorithms: vec![public_key.algorithm()],
+            subject: Some(request.info.subject.to_string()),
+            fingerprint: Some(public_key.fingerprint()?),
+            ..Default::default()
+        });
+    }
+    if let Ok(one_asymmetric_key) = OneAsymmetricKeyBorrowed::from_der(der) {
+        let algorithm = Algorithm::from_oid(&one_asymmetric_key.private_key_algorithm.algorithm)?;
+        let private_key_format = if algorithm.spec().composite.is_some() {
+            "composite seed"
+        } else {
+            match MlDsaPrivateKey::from_der(one_asymmetric_key.private_key)
+                .map_err(CryptoError::DerDecode)?
+            {
+                MlDsaPrivateKey::Seed(_) => "seed",
+                MlDsaPrivateKey::ExpandedKey(key)
+                    if key.as_bytes().len() == algorithm.spec().keypair_len() =>
+                {
+                    "legacy key pair"
+                }
+                MlDsaPrivateKey::ExpandedKey(_) => "expanded",
+                MlDsaPrivateKey::Both(_) => "both",
+            }
+        };
+        let secret_key = SecretKey::from_der(der)?;
+        return Ok(Report {
+            kind: "private key",
+            encoding: "DER",
+            expected_label: Some(OneAsymmetricKeyBorrowed::PEM_LABEL),
+            algorithms: vec![algorithm],
+            key_version: Some(one_asymmetric_key.version + 1),
+            private_key_format: Some(private_key_format),
+            fingerprint: Some(secret_key.public_key().fingerprint()?),
+            ..Default::default()
+        });
+    }
+    if SignatureEnvelopeBorrowed::from_der(der).is_ok() {
+        let envelope = SignatureEnvelope::from_der(der)?;
+        return Ok(Report {
+            kind: "signature envelope",
+            encoding: "DER",
+            expected_label: Some(SignatureEnvelopeBorrowed::PEM_LABEL),
+            algorithms: vec![envelope.signature.algorithm()],
+            fingerprint: Some(envelope.key_fingerprint),
+            ..Default::default()
+        });
+    }
+    let encrypted = EncryptedPrivateKeyInfo::from_der(der).map_err(CryptoError::DerDecode)?;
+    let encryption = describe_encryption(&encrypted.encryption_algorithm);
+    let report = match password {
+        Some(password) => {
+            let document = encrypted
+                .decrypt(password)
+                .map_err(CryptoError::Encryption)?;
+            inspect_der(document.as_bytes(), None)?
+        }
+        None => Report::default(),
+    };
+    Ok(Report {
+        kind: "encrypted private key",
+        encoding: "DER",
+        expected_label: Some(EncryptedPrivateKeyInfo::PEM_LABEL),
+        encryption: Some(encryption),
+        ..report
+    })
+}
+
+/// A raw signature is only recognisable by its length
+fn inspect_signature(bytes: &[u8]) -> Option<Report> {
+    let algorithms: Vec<Algorithm> = ALGORITHMS
+        .iter()
+        .filter(|spec| spec.signature_len == bytes.len())
+        .map(|spec| spec.algorithm)
+        .collect();
+    if algorithms.is_empty() {
+        return None;
+    }
+    Some(Report {
+        kind: "signature",
+        encoding: "raw",
+        algorithms,
+        ..Default::default()
+    })
+}
+
+fn describe_encryption(scheme: &EncryptionScheme<'_>) -> String {
+    let pbes2 = match scheme {
+        EncryptionScheme::Pbes2(pbes2) => pbes2,
+        EncryptionScheme::Pbes1(_) => return "PBES1".to_string(),
+        _ => return "unknown scheme".to_string(),
+    };
+    let kdf = match &pbes2.kdf {
+        pbes2::Kdf::Pbkdf2(params) => format!("PBKDF2 ({} iterations)", params.iteration_count),
+        pbes2::Kdf::Scrypt(_) => "scrypt".to_string(),
+        _ => "unknown KDF".to_string(),
+    };
+    let cipher = match pbes2.encryption {
+        pbes2::EncryptionScheme::Aes128Cbc { .. } => "AES-128-CBC",
+        pbes2::EncryptionScheme::Aes192Cbc { .. } => "AES-192-CBC",
+        pbes2::EncryptionScheme::Aes256Cbc { .. } => "AES-256-CBC",
+        _ => "other cipher",
+    };
+    format!("PBES2, {}, {}", kdf, cipher)
+}
+
+/// JSON string literal
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+impl Report {
+    fn label_ok(&self) -> Option<bool> {
+        Some(self.pem_label.as_deref()? == self.expected_label?)
+    }
+
+    fn to_text(&self) -> String {
+        let mut out = format!("Type: {}\nEncoding: {}\n", self.kind, self.encoding);
+        if let Some(label) = &self.pem_label {
+            match (self.label_ok(), self.expected_label) {
+                (Some(false), Some(expected)) => out.push_str(&format!(
+                    "PEM label: {} (incorrect, expected {})\n",
+                    label, expected
+                )),
+                _ => out.push_str(&format!("PEM label: {} (correct)\n", label)),
+            }
+        }
+        if let Some(encryption) = &self.encryption {
+            out.push_str(&format!("Encryption: {}\n", encryption));
+        }
+        for algorithm in &self.algorithms {
+            let spec = algorithm.spec();
+            out.push_str(&format!(
+                "Algorithm: {} ({})\n  Public key: {} bytes\n  Secret key: {} bytes\n  Signature: {} bytes\n",
+                spec.name, spec.oid, spec.public_key_len, spec.secret_key_len, spec.signature_len
+            ));
+        }
+        if let (Some(version), Some(format)) = (self.key_version, self.private_key_format) {
+            out.push_str(&format!(
+                "Private key: {}, OneAsymmetricKey v{}\n",
+                format, version
+            ));
+        }
+        if let Some(subject) = &self.subject {
+            out.push_str(&format!("Subject: {}\n", subject));
+        }
+        if let Some(fingerprint) = &self.fingerprint {
+            out.push_str(&format!("Fingerprint: {}\n", fingerprint));
+            out.push_str(&format!("Key ID: {}\n", fingerprint.key_id()));
+        }
+        out
+    }
+
+    fn to_json(&self) -> String {
+        let mut fields = vec![
+            format!("\"type\":{}", json_string(self.kind)),
+            format!("\"encoding\":{}", json_string(self.encoding)),
+        ];
+        if let Some(label) = &self.pem_label {
+            fields.push(format!("\"pem_label\":{}", json_string(label)));
+        }
+        if let Some(label_ok) = self.label_ok() {
+            fields.push(format!("\"pem_label_correct\":{}", label_ok));
+        }
+        if let Some(encryption) = &self.encryption {
+            fields.push(format!("\"encryption\":{}", json_string(encryption)));
+        }
+        let algorithms: Vec<String> = self
+            .algorithms
+            .iter()
+            .map(|algorithm| {
+                let spec = algorithm.spec();
+                format!(
+                    "{{\"name\":{},\"oid\":{},\"public_key_len\":{},\"secret_key_len\":{},\"signature_len\":{}}}",
+                    json_string(spec.name),
+                    json_string(spec.oid),
+                    spec.public_key_len,
+                    spec.secret_key_len,
+                    spec.signature_len
+                )
+            })
+            .collect();
+        fields.push(format!("\"algorithms\":[{}]", algorithms.join(",")));
+        if let Some(version) = self.key_version {
+            fields.push(format!("\"key_version\":{}", version));
+        }
+        if let Some(format) = self.private_key_format {
+            fields.push(format!("\"private_key_format\":{}", json_string(format)));
+        }
+        if let Some(subject) = &self.subject {
+            fields.push(format!("\"subject\":{}", json_string(subject)));
+        }
+        if let Some(fingerprint) = &self.fingerprint {
+            fields.push(format!(
+                "\"fingerprint\":{}",
+                json_string(&fingerprint.to_string())
+            ));
+            fields.push(format!("\"key_id\":{}", json_string(&fingerprint.key_id())));
+        }
+        format!("{{{}}}", fields.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crypto::{Kdf, PrivateKeyFormat};
+
+    #[test]
+    fn inspect_identifies_keys_and_signatures() {
+        let secret = SecretKey::from_seed(Algorithm::Mldsa65, &[7u8; 32]);
+        let public = secret.public_key();
+        let fingerprint = Some(public.fingerprint().unwrap());
+
+        let report = inspect(secret.to_pem().unwrap().as_bytes(), None).unwrap();
+        assert_eq!(report.kind, "private key");
+        assert_eq!(report.encoding, "PEM");
+        assert_eq!(report.label_ok(), Some(true));
+        assert_eq!(report.algorithms, vec![Algorithm::Mldsa65]);
+        assert_eq!(report.private_key_format, Some("both"));
+        assert_eq!(report.fingerprint, fingerprint);
+
+        let report = inspect(&public.to_der().unwrap(), None).unwrap();
+        assert_eq!(report.kind, "public key");
+        assert_eq!(report.encoding, "DER");
+        assert_eq!(report.fingerprint, fingerprint);
+
+        let options = x509::CertificateOptions::new(x509::parse_name("CN=inspect").unwrap(), 1);
+        let certificate = x509::self_signed(&secret, &options).unwrap();
+        let report = inspect(x509::to_pem(&certificate).unwrap().as_bytes(), None).unwrap();
+        assert_eq!(report.kind, "certificate");
+        assert_eq!(report.label_ok(), Some(true));
+        assert_eq!(report.subject.as_deref(), Some("CN=inspect"));
+        assert_eq!(report.fingerprint, fingerprint);
+
+        let request = x509::request(&secret, &options.subject, &Default::default()).unwrap();
+        let report = inspect(&der::Encode::to_der(&request).unwrap(), None).unwrap();
+        assert_eq!(report.kind, "certificate request");
+        assert_eq!(report.subject.as_deref(), Some("CN=inspect"));
+
+        let encrypted = secret
+            .to_encrypted_der(
+                PrivateKeyFormat::Seed.into(),
+                b"secret",
+                Kdf::Pbkdf2 { iterations: 1000 },
+            )
+            .unwrap();
+        let report = inspect(&encrypted, None).unwrap();
+        assert_eq!(report.kind, "encrypted private key");
+        assert!(report.algorithms.is_empty());
+        let report = inspect(&encrypted, Some(b"secret")).unwrap();
+        assert_eq!(report.private_key_format, Some("seed"));
+        assert_eq!(report.fingerprint, fingerprint);
+
+        // ML-DSA-44 and Dilithium2 signatures have the same length
+        let signature = SecretKey::from_seed(Algorithm::Mldsa44, &[7u8; 32])
+            .sign(b"message")
+            .unwrap();
+        let report = inspect(signature.as_bytes(), None).unwrap();
+        assert_eq!(report.kind, "signature");
+        assert_eq!(
+            report.algorithms,
+            vec![Algorithm::Dilithium2, Algorithm::Mldsa44]
+        );
+    }
+
+    #[test]
+    fn inspect_identifies_signature_envelopes() {
+        let secret = SecretKey::from_seed(Algorithm::Mldsa44, &[7u8; 32]);
+        let signature = secret.sign(b"message").unwrap();
+        let envelope = SignatureEnvelope::new(signature, &secret.public_key(), &[]).unwrap();
+
+        let report = inspect(envelope.to_pem().unwrap().as_bytes(), None).unwrap();
+        assert_eq!(report.kind, "signature envelope");
+        assert_eq!(report.label_ok(), Some(true));
+        assert_eq!(report.algorithms, vec![Algorithm::Mldsa44]);
+        assert_eq!(report.fingerprint, Some(envelope.key_fingerprint));
+    }
+
+    #[test]
+    fn inspect_reports_wrong_pem_label() {
+        let public = SecretKey::from_seed(Algorithm::Mldsa44, &[7u8; 32]).public_key();
+        let pem = public
+            .to_pem()
+            .unwrap()
+            .replace("PUBLIC KEY", "PRIVATE KEY");
+
+        let report = inspect(pem.as_bytes(), None).unwrap();
+        assert_eq!(report.kind, "public key");
+        assert_eq!(report.label_ok(), Some(false));
+        assert!(report
+            .to_json()
+            .contains("\"pem_label\":\"PRIVATE KEY\",\"pem_label_correct\":false"));
+    }
+
+    #[test]
+    fn inspect_rejects_unrecognised_input() {
+        assert!(inspect(b"not a key", None).is_err());
+    }
+}
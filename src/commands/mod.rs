@@ -1,10 +1,18 @@
 mod arg_enums;
-mod asc1_dilithium;
-mod error;
+mod ca;
+mod cert;
+mod cms_sign;
+mod cms_verify;
 mod generate;
+mod inspect;
 mod public;
+mod req;
 mod sign;
 mod utils;
 mod verify;
 
-pub use self::{generate::GenerateCmd, public::PublicCmd, sign::SignCmd, verify::VerifyCmd};
+pub use self::{
+    ca::CaCmd, cert::CertCmd, cms_sign::CmsSignCmd, cms_verify::CmsVerifyCmd,
+    generate::GenerateCmd, inspect::InspectCmd, public::PublicCmd, req::ReqCmd, sign::SignCmd,
+    verify::VerifyCmd,
+};
@@ -0,0 +1,157 @@
+use super::{arg_enums::Format, cert::ExtensionArgs, utils};
+use clap::Parser;
+use crypto::{x509, CryptoError};
+use der::Encode;
+
+#[derive(Debug, Clone, Parser)]
+#[clap(
+    name = "req",
+    about = "Create a PKCS#10 certificate signing request, or check one with --verify"
+)]
+pub struct ReqCmd {
+    ///Input format of the private key (PEM, DER, HEX, BASE64, BASE64URL or BASE58), detected
+    ///from the file when omitted
+    #[clap(long = "inform", value_name = "FORMAT")]
+    pub inform: Option<Format>,
+    ///Private key file, or a directory of keys to choose from with --key-id
+    #[clap(
+        long = "sec",
+        value_name = "FILE|DIR",
+        required_unless_present = "verify",
+        conflicts_with = "verify"
+    )]
+    pub in_path: Option<String>,
+    ///Use the key with this key ID or fingerprint
+    #[clap(long = "key-id", value_name = "ID")]
+    pub key_id: Option<String>,
+    ///Password for an encrypted private key (pass:PASSWORD, env:VAR or file:PATH)
+    #[clap(long = "passin", value_name = "SOURCE")]
+    pub passin: Option<String>,
+    ///Subject distinguished name, e.g. "CN=service,O=Example"
+    #[clap(
+        long = "subject",
+        value_name = "DN",
+        required_unless_present = "verify",
+        conflicts_with = "verify"
+    )]
+    pub subject: Option<String>,
+    #[clap(flatten)]
+    pub extensions: ExtensionArgs,
+    ///Output file
+    #[clap(long = "out", value_name = "FILE")]
+    pub out_path: Option<String>,
+    ///Output format (PEM, DER, HEX, BASE64, BASE64URL or BASE58)
+    #[clap(long = "outform", value_name = "FORMAT", default_value = "PEM")]
+    pub outform: Format,
+    ///Check the signature of the request given with --in and print its contents
+    #[clap(long = "verify", requires = "request_path")]
+    pub verify: bool,
+    ///Request to check, PEM, DER, hex or base64
+    #[clap(long = "in", value_name = "FILE", requires = "verify")]
+    pub request_path: Option<String>,
+}
+
+impl ReqCmd {
+    pub fn run(&self) -> Result<(), CryptoError> {
+        match (&self.request_path, &self.in_path, &self.subject) {
+            (Some(request_path), _, _) => self.verify(request_path),
+            (None, Some(in_path), Some(subject)) => self.create(in_path, subject),
+            _ => unreachable!("clap requires --sec and --subject without --verify"),
+        }
+    }
+
+    fn create(&self, in_path: &String, subject: &str) -> Result<(), CryptoError> {
+        let secret_key =
+            utils::select_secret_key(in_path, self.inform, &self.passin, &self.key_id)?;
+        let request = x509::request(
+            &secret_key,
+            &x509::parse_name(subject)?,
+            &self.extensions.to_options()?,
+        )?;
+        if self.outform == Format::Pem {
+            let pem = x509::request_to_pem(&request)?;
+            utils::output(pem.as_bytes(), &self.out_path, Format::Pem)?;
+        } else {
+            utils::output(&request.to_der()?, &self.out_path, self.outform)?;
+        }
+        utils::print_fingerprint(&secret_key.public_key())
+    }
+
+    fn verify(&self, request_path: &String) -> Result<(), CryptoError> {
+        let request = utils::decode_text(utils::read_file(request_path)?, None)
+            .and_then(|bytes| x509::decode_request(&bytes))
+            .map_err(|err| err.in_file(request_path))?;
+        let public_key =
+            x509::request_public_key(&request).map_err(|err| err.in_file(request_path))?;
+        let ver = x509::verify_request(&request).map_err(|err| err.in_file(request_path))?;
+        let extensions =
+            x509::requested_extensions(&request).map_err(|err| err.in_file(request_path))?;
+
+        let fingerprint = public_key.fingerprint()?;
+        println!("Subject: {}", request.info.subject);
+        println!("Algorithm: {}", public_key.algorithm());
+        println!("Fingerprint: {}", fingerprint);
+        println!("Key ID: {}", fingerprint.key_id());
+        for extension in &extensions {
+            println!(
+                "Requested extension: {}",
+                x509::describe_extension(extension)
+            );
+        }
+        println!("Verification: {:?}", ver);
+        if !ver {
+            return Err(CryptoError::VerificationFailed);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::commands::GenerateCmd;
+    use std::fs;
+
+    #[test]
+    fn req_create_and_verify() {
+        let sec_file = "req_sec_test".to_string();
+        let req_file = "req_req_test".to_string();
+        GenerateCmd::parse_from(["generate", "--algorithm", "mldsa65", "--out", &sec_file])
+            .run()
+            .unwrap();
+        for outform in ["PEM", "DER", "BASE64"] {
+            ReqCmd::parse_from([
+                "req",
+                "--sec",
+                &sec_file,
+                "--subject",
+                "CN=client,O=Example",
+                "--san",
+                "email:client@example.com",
+                "--out",
+                &req_file,
+                "--outform",
+                outform,
+            ])
+            .run()
+            .unwrap();
+            ReqCmd::parse_from(["req", "--verify", "--in", &req_file])
+                .run()
+                .unwrap();
+        }
+
+        // The last request was written as base64
+        let bytes = utils::decode_text(fs::read(&req_file).unwrap(), None).unwrap();
+        let mut request = x509::decode_request(&bytes).unwrap();
+        request.info.subject = x509::parse_name("CN=forged").unwrap();
+        fs::write(&req_file, request.to_der().unwrap()).unwrap();
+        assert!(matches!(
+            ReqCmd::parse_from(["req", "--verify", "--in", &req_file]).run(),
+            Err(CryptoError::VerificationFailed)
+        ));
+        assert!(ReqCmd::try_parse_from(["req", "--verify"]).is_err());
+        assert!(ReqCmd::try_parse_from(["req", "--sec", &sec_file]).is_err());
+        let _ = fs::remove_file(sec_file);
+        let _ = fs::remove_file(req_file);
+    }
+}
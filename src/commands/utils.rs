@@ -1,28 +1,99 @@
 use crate::commands::arg_enums::Format;
-use crate::commands::arg_enums::Format::Pem;
-use crate::commands::error::CryptoError;
-use std::fs::File;
-use std::io::prelude::*;
-use std::str::from_utf8;
-
-pub fn output(bytes: &[u8], output_path: &Option<String>, format: Format) {
-    match output_path {
-        Some(out_path) => {
-            let mut file = File::create(out_path).unwrap();
-            let _ = file.write(bytes);
-        }
-        None => {
-            if format == Pem {
-                let str = from_utf8(bytes).unwrap();
-                println!("{}", str);
-            } else {
-                println!("{:?}", bytes);
-            }
-        }
+use base64::{
+    alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    Engine as _,
+};
+use crypto::{
+    x509::{self, Certificate, CertificateList},
+    CryptoError, Fingerprint, Manifest, PublicKey, SecretKey,
+};
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, prelude::*};
+use std::path::Path;
+
+/// File argument that stands for stdin or stdout
+pub const STDIO: &str = "-";
+
+/// Standard base64, padded on output and with or without padding on input
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+/// URL safe base64, unpadded on output and with or without padding on input
+const BASE64URL: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::URL_SAFE,
+    GeneralPurposeConfig::new()
+        .with_encode_padding(false)
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Write `bytes` to `output_path`, or to stdout. PEM text and DER are written as they are,
+/// the text formats encode `bytes` on a single line.
+pub fn output(
+    bytes: &[u8],
+    output_path: &Option<String>,
+    format: Format,
+) -> Result<(), CryptoError> {
+    let encoded = match format {
+        Format::Pem | Format::Der => bytes.to_vec(),
+        Format::Hex => format!("{}\n", encode_hex(bytes)).into_bytes(),
+        Format::Base64 => format!("{}\n", BASE64.encode(bytes)).into_bytes(),
+        Format::Base64Url => format!("{}\n", BASE64URL.encode(bytes)).into_bytes(),
+        Format::Base58 => format!("{}\n", bs58::encode(bytes).into_string()).into_bytes(),
+    };
+    write_file(output_path.as_deref().unwrap_or(STDIO), &encoded)
+}
+
+/// Write `bytes` to `out_path`, or to stdout for `-`
+pub fn write_file(out_path: &str, bytes: &[u8]) -> Result<(), CryptoError> {
+    if out_path == STDIO {
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(bytes)?;
+        stdout.flush()?;
+    } else {
+        File::create(out_path)?.write_all(bytes)?;
     }
+    Ok(())
 }
 
+/// Undo the text encoding of `bytes`. Without `format`, hex and base64 in either alphabet are
+/// detected; base58 has to be named, its alphabet being a subset of base64's. PEM, DER and
+/// other binary input is returned unchanged.
+pub fn decode_text(bytes: Vec<u8>, format: Option<Format>) -> Result<Vec<u8>, CryptoError> {
+    let is_text = bytes
+        .iter()
+        .all(|b| b.is_ascii_graphic() || b.is_ascii_whitespace());
+    let text: String = String::from_utf8_lossy(&bytes)
+        .split_ascii_whitespace()
+        .collect();
+    let Some(format) = format else {
+        if !is_text || text.is_empty() || text.starts_with("-----BEGIN") {
+            return Ok(bytes);
+        }
+        let decoded = decode_hex(&text)
+            .or_else(|| BASE64.decode(&text).ok())
+            .or_else(|| BASE64URL.decode(&text).ok());
+        return Ok(decoded.unwrap_or(bytes));
+    };
+    let decoded = match format {
+        Format::Pem | Format::Der => return Ok(bytes),
+        Format::Hex => decode_hex(&text),
+        Format::Base64 => BASE64.decode(&text).ok(),
+        Format::Base64Url => BASE64URL.decode(&text).ok(),
+        Format::Base58 => bs58::decode(&text).into_vec().ok(),
+    };
+    decoded.ok_or_else(|| CryptoError::InvalidFormat(format!("input is not valid {:?}", format)))
+}
+
+/// Read `in_path`, or stdin for `-`
 pub fn read_file(in_path: &String) -> Result<Vec<u8>, CryptoError> {
+    if in_path == STDIO {
+        let mut contents = vec![];
+        io::stdin().read_to_end(&mut contents)?;
+        return Ok(contents);
+    }
     let mut file = match File::open(in_path) {
         Ok(f) => f,
         Err(err) => return Err(CryptoError::Io(err)),
@@ -34,3 +105,224 @@ pub fn read_file(in_path: &String) -> Result<Vec<u8>, CryptoError> {
     };
     Ok(contents)
 }
+
+/// Fail when more than one of `paths` is `-`, as stdin can only be read once
+pub fn check_stdin<'a>(
+    paths: impl IntoIterator<Item = Option<&'a String>>,
+) -> Result<(), CryptoError> {
+    let readers = paths
+        .into_iter()
+        .flatten()
+        .filter(|path| *path == STDIO)
+        .count();
+    if readers > 1 {
+        return Err(CryptoError::InvalidArgument(
+            "only one input can be read from stdin (-)".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Resolve an OpenSSL style password source: `pass:PASSWORD`, `env:VAR` or `file:PATH`
+/// (first line of the file)
+pub fn read_password(source: &str) -> Result<Vec<u8>, CryptoError> {
+    if let Some(password) = source.strip_prefix("pass:") {
+        Ok(password.as_bytes().to_vec())
+    } else if let Some(var) = source.strip_prefix("env:") {
+        env::var(var).map(String::into_bytes).map_err(|_| {
+            CryptoError::InvalidPassword(format!("environment variable {} is not set", var))
+        })
+    } else if let Some(path) = source.strip_prefix("file:") {
+        let contents = read_file(&path.to_string())?;
+        let line = contents.split(|b| *b == b'\n').next().unwrap_or_default();
+        Ok(line.strip_suffix(b"\r").unwrap_or(line).to_vec())
+    } else {
+        Err(CryptoError::InvalidPassword(source.to_string()))
+    }
+}
+
+/// Context string from `--context TEXT` or `--context-hex HEX`, empty when neither is given
+pub fn read_context(text: &Option<String>, hex: &Option<String>) -> Result<Vec<u8>, CryptoError> {
+    match (text, hex) {
+        (Some(text), _) => Ok(text.as_bytes().to_vec()),
+        (None, Some(hex)) => decode_hex(hex)
+            .ok_or_else(|| CryptoError::InvalidContext(format!("{} is not valid hex", hex))),
+        (None, None) => Ok(Vec::new()),
+    }
+}
+
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Read a private key, detecting PEM/DER and encryption unless `inform` overrides the encoding
+pub fn read_secret_key(
+    in_path: &String,
+    inform: Option<Format>,
+    passin: &Option<String>,
+) -> Result<SecretKey, CryptoError> {
+    decode_secret_key(read_file(in_path)?, in_path, inform, passin)
+}
+
+/// [`read_secret_key`] for the contents of `in_path` that have already been read
+pub fn decode_secret_key(
+    contents: Vec<u8>,
+    in_path: &str,
+    inform: Option<Format>,
+    passin: &Option<String>,
+) -> Result<SecretKey, CryptoError> {
+    let bytes = decode_text(contents, inform).map_err(|err| err.in_file(in_path))?;
+    let password = passin.as_deref().map(read_password).transpose()?;
+    // Text encodings carry DER, which is then detected like a bare DER file
+    let secret_key = match (password, inform) {
+        (Some(password), Some(Format::Pem)) => SecretKey::from_encrypted_pem(&bytes, &password),
+        (Some(password), Some(Format::Der)) => SecretKey::from_encrypted_der(&bytes, &password),
+        (None, Some(Format::Pem)) => SecretKey::from_pem(&bytes),
+        (None, Some(Format::Der)) => SecretKey::from_der(&bytes),
+        (password, _) => SecretKey::decode(&bytes, password.as_deref()),
+    };
+    secret_key.map_err(|err| err.in_file(in_path))
+}
+
+/// Read a certificate: PEM, DER, or DER in hex or base64
+pub fn read_certificate(in_path: &String) -> Result<Certificate, CryptoError> {
+    decode_text(read_file(in_path)?, None)
+        .and_then(|bytes| x509::decode(&bytes))
+        .map_err(|err| err.in_file(in_path))
+}
+
+/// Every certificate of the PEM bundles or DER certificates at `paths`
+pub fn read_certificates(paths: &[String]) -> Result<Vec<Certificate>, CryptoError> {
+    let mut certificates = Vec::new();
+    for path in paths {
+        let bundle = decode_text(read_file(path)?, None)
+            .and_then(|bytes| x509::decode_bundle(&bytes))
+            .map_err(|err| err.in_file(path))?;
+        certificates.extend(bundle);
+    }
+    Ok(certificates)
+}
+
+/// CRLs at `paths`, one PEM or DER CRL per file
+pub fn read_crls(paths: &[String]) -> Result<Vec<CertificateList>, CryptoError> {
+    paths
+        .iter()
+        .map(|path| {
+            decode_text(read_file(path)?, None)
+                .and_then(|bytes| x509::decode_crl(&bytes))
+                .map_err(|err| err.in_file(path))
+        })
+        .collect()
+}
+
+/// Read a public key, detecting PEM/DER unless `inform` overrides the encoding
+pub fn read_public_key(in_path: &String, inform: Option<Format>) -> Result<PublicKey, CryptoError> {
+    let bytes = decode_text(read_file(in_path)?, inform).map_err(|err| err.in_file(in_path))?;
+    let public_key = match inform {
+        Some(Format::Pem) => PublicKey::from_pem(&bytes),
+        Some(Format::Der) => PublicKey::from_der(&bytes),
+        _ => PublicKey::decode(&bytes),
+    };
+    public_key.map_err(|err| err.in_file(in_path))
+}
+
+/// Read the private key at `path`. With `key_id`, `path` may also be a directory, from which
+/// the key whose public key fingerprint matches is taken; a single file must match it.
+pub fn select_secret_key(
+    path: &String,
+    inform: Option<Format>,
+    passin: &Option<String>,
+    key_id: &Option<String>,
+) -> Result<SecretKey, CryptoError> {
+    select_key(path, key_id, |path| {
+        let secret_key = read_secret_key(path, inform, passin)?;
+        let fingerprint = secret_key.public_key().fingerprint()?;
+        Ok((secret_key, fingerprint))
+    })
+}
+
+/// Read the public key at `path`, selecting it by `key_id` like [`select_secret_key`]
+pub fn select_public_key(
+    path: &String,
+    inform: Option<Format>,
+    key_id: &Option<String>,
+) -> Result<PublicKey, CryptoError> {
+    select_key(path, key_id, |path| {
+        let public_key = read_public_key(path, inform)?;
+        let fingerprint = public_key.fingerprint()?;
+        Ok((public_key, fingerprint))
+    })
+}
+
+fn select_key<K>(
+    path: &String,
+    key_id: &Option<String>,
+    read: impl Fn(&String) -> Result<(K, Fingerprint), CryptoError>,
+) -> Result<K, CryptoError> {
+    let Some(key_id) = key_id else {
+        return Ok(read(path)?.0);
+    };
+    if !Path::new(path).is_dir() {
+        let (key, fingerprint) = read(path)?;
+        if !fingerprint.matches(key_id) {
+            return Err(CryptoError::KeyNotFound(key_id.clone()).in_file(path));
+        }
+        return Ok(key);
+    }
+    let mut paths = fs::read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.sort();
+    // Files that are not keys, or that cannot be decrypted with `passin`, are skipped
+    paths
+        .iter()
+        .filter(|path| path.is_file())
+        .filter_map(|path| read(&path.to_string_lossy().into_owned()).ok())
+        .find(|(_, fingerprint)| fingerprint.matches(key_id))
+        .map(|(key, _)| key)
+        .ok_or_else(|| CryptoError::KeyNotFound(key_id.clone()).in_file(path))
+}
+
+/// Print the fingerprint and key ID of `public_key` to stderr, so they do not mix with a key
+/// written to stdout
+pub fn print_fingerprint(public_key: &PublicKey) -> Result<(), CryptoError> {
+    let fingerprint = public_key.fingerprint()?;
+    eprintln!("Fingerprint: {}", fingerprint);
+    eprintln!("Key ID: {}", fingerprint.key_id());
+    Ok(())
+}
+
+/// Manifest of the files in `dir`, leaving out `excluded` paths (the manifest and signature
+/// being written) when they lie inside it
+pub fn read_manifest_dir(
+    dir: &String,
+    excluded: &[Option<&String>],
+) -> Result<Manifest, CryptoError> {
+    let root = fs::canonicalize(dir)?;
+    let mut manifest = Manifest::from_dir(&root).map_err(|err| err.in_file(dir))?;
+    for path in excluded.iter().flatten().filter(|path| **path != STDIO) {
+        let path = Path::new(path);
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let (Ok(parent), Some(name)) = (fs::canonicalize(parent), path.file_name()) else {
+            continue;
+        };
+        if let Ok(relative) = parent.join(name).strip_prefix(&root) {
+            let relative: Vec<_> = relative.iter().map(|part| part.to_string_lossy()).collect();
+            manifest.entries.remove(&relative.join("/"));
+        }
+    }
+    Ok(manifest)
+}
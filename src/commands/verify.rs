@@ -1,54 +1,115 @@
-use super::{arg_enums::Format, utils};
-use crate::commands::{
-    asc1_dilithium::{
-        SubjectPublicKeyInfoBorrowed, SubjectPublicKeyInfoOwned, OID_DILITHIUM2, OID_DILITHIUM3,
-        OID_DILITHIUM5, OID_MLDSA44, OID_MLDSA65, OID_MLDSA87,
-    },
-    error::CryptoError,
-};
+use super::{arg_enums::Format, cert::ChainArgs, utils};
 use clap::Parser;
-use crystals_dilithium::{dilithium2, dilithium3, dilithium5, ml_dsa_44, ml_dsa_65, ml_dsa_87};
-use der::{Decode, DecodePem};
+use crypto::{chain, x509, CryptoError, Manifest, Signature, SignatureEnvelope, ALGORITHMS};
 // use sha2::{Digest, Sha256};
-use std::{fs::File, io::Read};
 
 #[derive(Debug, Clone, Parser)]
 #[clap(name = "verify", about = "Signature verification")]
 pub struct VerifyCmd {
-    ///Input format (DER or PEM)
-    #[clap(long = "inform", value_name = "PEM|DER", default_value = "PEM")]
-    pub inform: Format,
-    ///Input public key
-    #[clap(long = "pub", value_name = "FILE")]
-    pub pub_path: String,
-    ///Input signature
+    ///Input format (PEM, DER, HEX, BASE64, BASE64URL or BASE58), detected from the file
+    ///when omitted
+    #[clap(long = "inform", value_name = "FORMAT")]
+    pub inform: Option<Format>,
+    ///Public key file, or a directory of keys to choose from with --key-id
+    #[clap(
+        long = "pub",
+        value_name = "FILE|DIR",
+        required_unless_present = "cert_path"
+    )]
+    pub pub_path: Option<String>,
+    ///Use the key with this key ID or fingerprint
+    #[clap(long = "key-id", value_name = "ID")]
+    pub key_id: Option<String>,
+    ///Signer certificate to take the public key from, in place of --pub; its path to one of
+    ///the --trusted certificates is validated first
+    #[clap(long = "cert", value_name = "FILE", requires = "trusted")]
+    pub cert_path: Option<String>,
+    #[clap(flatten)]
+    pub chain: ChainArgs,
+    ///Input signature, raw or a DER/PEM signature envelope
     #[clap(long = "sig", value_name = "FILE")]
     pub sig_path: String,
+    ///Text encoding of the signature (HEX, BASE64, BASE64URL or BASE58), hex and base64 are
+    ///detected when omitted
+    #[clap(long = "sigform", value_name = "FORMAT")]
+    pub sigform: Option<Format>,
     ///Input file for verification
-    #[clap(long = "file", value_name = "FILE")]
-    pub file_path: String,
+    #[clap(long = "file", value_name = "FILE", required_unless_present = "dir")]
+    pub file_path: Option<String>,
+    ///Check every file in this directory against the signed manifest
+    #[clap(
+        long = "dir",
+        value_name = "DIR",
+        conflicts_with = "file_path",
+        requires = "manifest_path"
+    )]
+    pub dir: Option<String>,
+    ///Signed manifest for --dir
+    #[clap(long = "manifest", value_name = "FILE", requires = "dir")]
+    pub manifest_path: Option<String>,
+    ///Print only the verification result (true or false)
+    #[clap(long = "brief")]
+    pub brief: bool,
+    ///Context string (ML-DSA only, up to 255 bytes)
+    #[clap(long = "context", value_name = "TEXT", conflicts_with = "context_hex")]
+    pub context: Option<String>,
+    ///Context string as hex
+    #[clap(long = "context-hex", value_name = "HEX")]
+    pub context_hex: Option<String>,
 }
 
 impl VerifyCmd {
     pub fn run(&self) -> Result<(), CryptoError> {
-        let bytes = utils::read_file(&self.pub_path)?;
-        let sig_bytes = utils::read_file(&self.sig_path)?;
-
-        let algorithm: String;
-        let bytes_public_key: Vec<u8>;
-        if self.inform == Format::Der {
-            let public_key = SubjectPublicKeyInfoBorrowed::from_der(&bytes).unwrap();
-            algorithm = public_key.algorithm.algorithm.to_string();
-            bytes_public_key = public_key.subject_public_key.to_vec();
-        } else {
-            let public_key = SubjectPublicKeyInfoOwned::from_pem(&bytes).unwrap();
-            algorithm = public_key.algorithm.algorithm.to_string();
-            bytes_public_key = public_key.subject_public_key.as_bytes().unwrap().to_vec();
+        // Not a clap conflict: clap counts a requirement as met when the required argument
+        // conflicts with a present one, which would let --trusted through without --cert
+        if self.cert_path.is_some() && (self.pub_path.is_some() || self.key_id.is_some()) {
+            return Err(CryptoError::InvalidArgument(
+                "--cert cannot be combined with --pub or --key-id".to_string(),
+            ));
         }
+        utils::check_stdin(
+            [
+                self.pub_path.as_ref(),
+                self.cert_path.as_ref(),
+                Some(&self.sig_path),
+                self.file_path.as_ref(),
+                self.manifest_path.as_ref(),
+            ]
+            .into_iter()
+            .chain(self.chain.trusted.iter().map(Some))
+            .chain(self.chain.intermediates.iter().map(Some))
+            .chain(self.chain.crls.iter().map(Some)),
+        )?;
+        let sig_bytes = utils::decode_text(utils::read_file(&self.sig_path)?, self.sigform)
+            .map_err(|err| err.in_file(&self.sig_path))?;
+        let envelope = read_envelope(&sig_bytes).map_err(|err| err.in_file(&self.sig_path))?;
+        // An envelope names the signer's key, which then selects it from a directory
+        let key_id = self.key_id.clone().or_else(|| {
+            envelope
+                .as_ref()
+                .map(|envelope| envelope.key_fingerprint.to_hex())
+        });
+        let (public_key, certificate) = match (&self.cert_path, &self.pub_path) {
+            (Some(cert_path), _) => {
+                let certificate = utils::read_certificate(cert_path)?;
+                self.chain.validate(&certificate)?;
+                chain::check_signing_usage(&certificate)?;
+                let public_key =
+                    x509::public_key(&certificate).map_err(|err| err.in_file(cert_path))?;
+                (public_key, Some(certificate))
+            }
+            (None, Some(pub_path)) => (
+                utils::select_public_key(pub_path, self.inform, &key_id)?,
+                None,
+            ),
+            _ => unreachable!("clap requires --pub or --cert"),
+        };
 
-        let algorithm_str: &str = &algorithm;
-
-        let mut file = File::open(&self.file_path)?;
+        let message = match (&self.manifest_path, &self.file_path) {
+            (Some(manifest_path), _) => utils::read_file(manifest_path)?,
+            (None, Some(file_path)) => utils::read_file(file_path)?,
+            _ => unreachable!("clap requires --file or --dir with --manifest"),
+        };
 
         // let mut hasher = Sha256::new();
         // let mut buffer = [0; 4096];
@@ -63,83 +124,87 @@ impl VerifyCmd {
 
         // let message_hash = hasher.finalize();
 
-        let mut message = Vec::new();
-        file.read_to_end(&mut message)?;
-
-        let ver = match algorithm_str {
-            OID_DILITHIUM2 => {
-                if bytes_public_key.len() != dilithium2::PUBLICKEYBYTES {
-                    return Err(CryptoError::InvalidLengthPublicKey(format!(
-                        "A public key of length {:?} is expected a signature of length {:?}",
-                        dilithium2::PUBLICKEYBYTES,
-                        dilithium2::SIGNBYTES,
-                    )));
+        let context = match (&self.context, &self.context_hex) {
+            (None, None) => None,
+            (text, hex) => Some(utils::read_context(text, hex)?),
+        };
+        let (signature, context) = match envelope {
+            Some(envelope) => {
+                if context.is_some_and(|context| context != envelope.context) {
+                    return Err(CryptoError::InvalidContext(
+                        "does not match the context in the signature envelope".to_string(),
+                    ));
                 }
-                let public = dilithium2::PublicKey::from_bytes(&bytes_public_key);
-                public.verify(&message, &sig_bytes)
-            }
-            OID_DILITHIUM3 => {
-                if bytes_public_key.len() != dilithium3::PUBLICKEYBYTES {
-                    return Err(CryptoError::InvalidLengthPublicKey(format!(
-                        "A public key of length {:?} is expected a signature of length {:?}",
-                        dilithium2::PUBLICKEYBYTES,
-                        dilithium2::SIGNBYTES,
-                    )));
+                if !self.brief {
+                    if let Some(signing_time) = envelope.signing_time {
+                        let signing_time =
+                            der::asn1::GeneralizedTime::from_system_time(signing_time)?;
+                        println!("Signing time: {}", signing_time.to_date_time());
+                    }
                 }
-                let public = dilithium3::PublicKey::from_bytes(&bytes_public_key);
-                public.verify(&message, &sig_bytes)
+                (envelope.signature, envelope.context)
             }
-            OID_DILITHIUM5 => {
-                if bytes_public_key.len() != dilithium5::PUBLICKEYBYTES {
-                    return Err(CryptoError::InvalidLengthPublicKey(format!(
-                        "A public key of length {:?} is expected a signature of length {:?}",
-                        dilithium2::PUBLICKEYBYTES,
-                        dilithium2::SIGNBYTES,
-                    )));
-                }
-                let public = dilithium5::PublicKey::from_bytes(&bytes_public_key);
-                public.verify(&message, &sig_bytes)
-            }
-            OID_MLDSA44 => {
-                if bytes_public_key.len() != ml_dsa_44::PUBLICKEYBYTES {
-                    return Err(CryptoError::InvalidLengthPublicKey(format!(
-                        "A public key of length {:?} is expected a signature of length {:?}",
-                        ml_dsa_44::PUBLICKEYBYTES,
-                        ml_dsa_44::SIGNBYTES,
-                    )));
-                }
-                let public = ml_dsa_44::PublicKey::from_bytes(&bytes_public_key);
-                public.verify(&message, &sig_bytes, None)
+            None => {
+                let signature = Signature::from_bytes(public_key.algorithm(), &sig_bytes)
+                    .map_err(|err| err.in_file(&self.sig_path))?;
+                (signature, context.unwrap_or_default())
             }
-            OID_MLDSA65 => {
-                if bytes_public_key.len() != ml_dsa_65::PUBLICKEYBYTES {
-                    return Err(CryptoError::InvalidLengthPublicKey(format!(
-                        "A public key of length {:?} is expected a signature of length {:?}",
-                        ml_dsa_65::PUBLICKEYBYTES,
-                        ml_dsa_65::SIGNBYTES,
-                    )));
+        };
+        let mut ver = public_key.verify_with_context(&message, &signature, &context)?;
+        // The manifest is only trusted once its signature checks out
+        if let (true, Some(dir), Some(manifest_path)) = (ver, &self.dir, &self.manifest_path) {
+            let manifest = Manifest::parse(&message).map_err(|err| err.in_file(manifest_path))?;
+            let excluded = [Some(manifest_path), Some(&self.sig_path)];
+            let diff = manifest.diff(&utils::read_manifest_dir(dir, &excluded)?);
+            if !self.brief {
+                for (status, paths) in [
+                    ("Missing", &diff.missing),
+                    ("Extra", &diff.extra),
+                    ("Modified", &diff.modified),
+                ] {
+                    for path in paths {
+                        println!("{}: {}", status, path);
+                    }
                 }
-                let public = ml_dsa_65::PublicKey::from_bytes(&bytes_public_key);
-                public.verify(&message, &sig_bytes, None)
             }
-            OID_MLDSA87 => {
-                if bytes_public_key.len() != ml_dsa_87::PUBLICKEYBYTES {
-                    return Err(CryptoError::InvalidLengthPublicKey(format!(
-                        "A public key of length {:?} is expected a signature of length {:?}",
-                        ml_dsa_87::PUBLICKEYBYTES,
-                        ml_dsa_87::SIGNBYTES,
-                    )));
-                }
-                let public = ml_dsa_87::PublicKey::from_bytes(&bytes_public_key);
-                public.verify(&message, &sig_bytes, None)
+            ver = diff.is_empty();
+        }
+        if self.brief {
+            println!("{:?}", ver);
+        } else {
+            if let Some(certificate) = &certificate {
+                println!("Subject: {}", certificate.tbs_certificate.subject);
             }
-            _ => return Err(CryptoError::InvalidLengthSignature(sig_bytes.len())),
-        };
-        println!("Verification: {:?}", ver);
+            let fingerprint = public_key.fingerprint()?;
+            println!("Fingerprint: {}", fingerprint);
+            println!("Key ID: {}", fingerprint.key_id());
+            println!("Verification: {:?}", ver);
+        }
+        if !ver {
+            return Err(CryptoError::VerificationFailed);
+        }
         Ok(())
     }
 }
 
+/// A signature envelope, or `None` for bare signature bytes. PEM and DER input is decoded as
+/// an envelope first, since an envelope can have the length of some parameter set's signature;
+/// it is only taken as bare bytes when it does not decode and has such a length.
+fn read_envelope(bytes: &[u8]) -> Result<Option<SignatureEnvelope>, CryptoError> {
+    let raw = ALGORITHMS
+        .iter()
+        .any(|spec| spec.signature_len == bytes.len());
+    let armored = bytes.trim_ascii_start().starts_with(b"-----BEGIN ");
+    if !(armored || bytes.first() == Some(&0x30)) {
+        return Ok(None);
+    }
+    match SignatureEnvelope::decode(bytes) {
+        Ok(envelope) => Ok(Some(envelope)),
+        Err(_) if raw && !armored => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -212,6 +277,460 @@ mod test {
         }
     }
 
+    #[test]
+    fn verify_modified_file_returns_verification_failed() {
+        let sec_file = "ver_sec_test_modified".to_string();
+        let pub_file = "ver_pub_test_modified".to_string();
+        let sig_file = "ver_sig_test_modified".to_string();
+        let msg_file = "ver_msg_test_modified".to_string();
+        fs::write(&msg_file, b"original").unwrap();
+
+        let generate =
+            GenerateCmd::parse_from(["generate", "--algorithm", "mldsa44", "--out", &sec_file]);
+        let public = PublicCmd::parse_from(["public", "--in", &sec_file, "--out", &pub_file]);
+        let sign = SignCmd::parse_from([
+            "sign", "--sec", &sec_file, "--out", &sig_file, "--file", &msg_file,
+        ]);
+        let verify = VerifyCmd::parse_from([
+            "verify", "--sig", &sig_file, "--pub", &pub_file, "--file", &msg_file, "--brief",
+        ]);
+
+        assert!(generate.run().is_ok());
+        assert!(public.run().is_ok());
+        assert!(sign.run().is_ok());
+        fs::write(&msg_file, b"modified").unwrap();
+        assert!(matches!(verify.run(), Err(CryptoError::VerificationFailed)));
+
+        cleanup(&[sec_file, pub_file, sig_file, msg_file]);
+    }
+
+    #[test]
+    fn verify_selects_public_key_by_key_id() {
+        let dir = "ver_dir_test_key_id".to_string();
+        let sec_file = "ver_sec_test_key_id".to_string();
+        let sig_file = "ver_sig_test_key_id".to_string();
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut key_id = String::new();
+        for (i, alg) in ["mldsa44", "mldsa65"].iter().enumerate() {
+            let secret_key = crypto::generate(alg.parse().unwrap());
+            let public_key = secret_key.public_key();
+            fs::write(format!("{}/{}.pem", dir, i), public_key.to_pem().unwrap()).unwrap();
+            fs::write(&sec_file, secret_key.to_pem().unwrap()).unwrap();
+            key_id = public_key.fingerprint().unwrap().key_id();
+        }
+        fs::write(format!("{}/notes.txt", dir), b"not a key").unwrap();
+
+        let sign = SignCmd::parse_from([
+            "sign", "--sec", &sec_file, "--key-id", &key_id, "--out", &sig_file, "--file",
+            &sec_file,
+        ]);
+        let verify = VerifyCmd::parse_from([
+            "verify", "--sig", &sig_file, "--pub", &dir, "--key-id", &key_id, "--file", &sec_file,
+            "--brief",
+        ]);
+        let unknown = VerifyCmd::parse_from([
+            "verify",
+            "--sig",
+            &sig_file,
+            "--pub",
+            &dir,
+            "--key-id",
+            "0000000000000000",
+            "--file",
+            &sec_file,
+        ]);
+        let signed = sign.run();
+        let verified = verify.run();
+        let unknown = unknown.run();
+        cleanup(&[sec_file, sig_file]);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(signed.is_ok());
+        assert!(verified.is_ok());
+        match unknown {
+            Err(CryptoError::File { source, .. }) => {
+                assert!(matches!(*source, CryptoError::KeyNotFound(_)))
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn verify_checks_the_context() {
+        let sec_file = "ver_sec_test_context".to_string();
+        let pub_file = "ver_pub_test_context".to_string();
+        let sig_file = "ver_sig_test_context".to_string();
+
+        let generate =
+            GenerateCmd::parse_from(["generate", "--algorithm", "mldsa65", "--out", &sec_file]);
+        let public = PublicCmd::parse_from(["public", "--in", &sec_file, "--out", &pub_file]);
+        let sign = SignCmd::parse_from([
+            "sign",
+            "--sec",
+            &sec_file,
+            "--out",
+            &sig_file,
+            "--file",
+            &sec_file,
+            "--context",
+            "vote",
+        ]);
+        let verify = |context: &[&str]| {
+            let mut args = vec![
+                "verify", "--sig", &sig_file, "--pub", &pub_file, "--file", &sec_file, "--brief",
+            ];
+            args.extend_from_slice(context);
+            VerifyCmd::parse_from(args).run()
+        };
+
+        assert!(generate.run().is_ok());
+        assert!(public.run().is_ok());
+        assert!(sign.run().is_ok());
+        let same = verify(&["--context-hex", "766f7465"]);
+        let other = verify(&["--context", "transaction"]);
+        let bad_hex = verify(&["--context-hex", "766"]);
+        cleanup(&[sec_file, pub_file, sig_file]);
+
+        assert!(same.is_ok());
+        assert!(matches!(other, Err(CryptoError::VerificationFailed)));
+        assert!(matches!(bad_hex, Err(CryptoError::InvalidContext(_))));
+    }
+
+    #[test]
+    fn verify_reads_signature_envelopes() {
+        let dir = "ver_dir_test_envelope".to_string();
+        let sec_file = "ver_sec_test_envelope".to_string();
+        let sig_file = "ver_sig_test_envelope".to_string();
+        fs::create_dir_all(&dir).unwrap();
+
+        let generate =
+            GenerateCmd::parse_from(["generate", "--algorithm", "mldsa87", "--out", &sec_file]);
+        let public = PublicCmd::parse_from([
+            "public",
+            "--in",
+            &sec_file,
+            "--out",
+            &format!("{}/signer.pem", dir),
+        ]);
+        assert!(generate.run().is_ok());
+        assert!(public.run().is_ok());
+
+        for outform in ["PEM", "DER"] {
+            let sign = SignCmd::parse_from([
+                "sign",
+                "--sec",
+                &sec_file,
+                "--out",
+                &sig_file,
+                "--file",
+                &sec_file,
+                "--context",
+                "vote",
+                "--outform",
+                outform,
+                "--signing-time",
+            ]);
+            // Context and signer key come from the envelope
+            let verify = VerifyCmd::parse_from([
+                "verify", "--sig", &sig_file, "--pub", &dir, "--file", &sec_file, "--brief",
+            ]);
+            let other_context = VerifyCmd::parse_from([
+                "verify",
+                "--sig",
+                &sig_file,
+                "--pub",
+                &dir,
+                "--file",
+                &sec_file,
+                "--context",
+                "transaction",
+            ]);
+            assert!(sign.run().is_ok());
+            assert!(verify.run().is_ok());
+            assert!(matches!(
+                other_context.run(),
+                Err(CryptoError::InvalidContext(_))
+            ));
+        }
+
+        cleanup(&[sec_file, sig_file]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn verify_reads_envelopes_with_the_length_of_a_bare_signature() {
+        for alg in ["mldsa44", "mldsa65"] {
+            let sec_file = format!("ver_sec_test_envelope_len_{}", alg);
+            let pub_file = format!("ver_pub_test_envelope_len_{}", alg);
+            let sig_file = format!("ver_sig_test_envelope_len_{}", alg);
+            GenerateCmd::parse_from(["generate", "--algorithm", alg, "--out", &sec_file])
+                .run()
+                .unwrap();
+            PublicCmd::parse_from(["public", "--in", &sec_file, "--out", &pub_file])
+                .run()
+                .unwrap();
+            SignCmd::parse_from([
+                "sign",
+                "--sec",
+                &sec_file,
+                "--out",
+                &sig_file,
+                "--file",
+                &sec_file,
+                "--context",
+                "abc",
+                "--outform",
+                "DER",
+            ])
+            .run()
+            .unwrap();
+            // A 3-byte context makes the envelope as long as a composite signature
+            let length = fs::metadata(&sig_file).unwrap().len() as usize;
+            let result = VerifyCmd::parse_from([
+                "verify", "--pub", &pub_file, "--sig", &sig_file, "--file", &sec_file,
+            ])
+            .run();
+            cleanup(&[sec_file, pub_file, sig_file]);
+
+            assert!(ALGORITHMS.iter().any(|spec| spec.signature_len == length));
+            assert!(result.is_ok());
+        }
+    }
+
+    #[test]
+    fn verify_checks_a_signed_manifest() {
+        let dir = "ver_dir_test_manifest".to_string();
+        let sec_file = "ver_sec_test_manifest".to_string();
+        let pub_file = "ver_pub_test_manifest".to_string();
+        // The manifest and signature live in the release directory and are left out of it
+        let manifest_file = format!("{}/SHA256SUMS", dir);
+        let sig_file = format!("{}/SHA256SUMS.sig", dir);
+        fs::create_dir_all(format!("{}/bin", dir)).unwrap();
+        fs::write(format!("{}/bin/tool", dir), b"tool").unwrap();
+        fs::write(format!("{}/README", dir), b"readme").unwrap();
+
+        let generate =
+            GenerateCmd::parse_from(["generate", "--algorithm", "mldsa44", "--out", &sec_file]);
+        let public = PublicCmd::parse_from(["public", "--in", &sec_file, "--out", &pub_file]);
+        let sign = SignCmd::parse_from([
+            "sign",
+            "--sec",
+            &sec_file,
+            "--dir",
+            &dir,
+            "--manifest",
+            &manifest_file,
+            "--out",
+            &sig_file,
+        ]);
+        let verify = VerifyCmd::parse_from([
+            "verify",
+            "--pub",
+            &pub_file,
+            "--sig",
+            &sig_file,
+            "--dir",
+            &dir,
+            "--manifest",
+            &manifest_file,
+        ]);
+        assert!(generate.run().is_ok());
+        assert!(public.run().is_ok());
+        assert!(sign.run().is_ok());
+        assert!(fs::read_to_string(&manifest_file)
+            .unwrap()
+            .ends_with("  bin/tool\n"));
+        assert!(verify.run().is_ok());
+
+        fs::write(format!("{}/extra", dir), b"extra").unwrap();
+        assert!(matches!(verify.run(), Err(CryptoError::VerificationFailed)));
+        fs::remove_file(format!("{}/extra", dir)).unwrap();
+        fs::write(format!("{}/README", dir), b"changed").unwrap();
+        assert!(matches!(verify.run(), Err(CryptoError::VerificationFailed)));
+
+        cleanup(&[sec_file, pub_file]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn verify_reads_text_encodings() {
+        let sec_file = "ver_sec_test_text".to_string();
+        let pub_file = "ver_pub_test_text".to_string();
+        let sig_file = "ver_sig_test_text".to_string();
+
+        for (format, detected) in [
+            ("HEX", true),
+            ("BASE64", true),
+            ("BASE64URL", true),
+            ("BASE58", false),
+        ] {
+            let generate = GenerateCmd::parse_from([
+                "generate",
+                "--algorithm",
+                "mldsa44",
+                "--out",
+                &sec_file,
+                "--outform",
+                format,
+            ]);
+            let public = PublicCmd::parse_from([
+                "public",
+                "--in",
+                &sec_file,
+                "--inform",
+                format,
+                "--out",
+                &pub_file,
+                "--outform",
+                format,
+            ]);
+            let sign = SignCmd::parse_from([
+                "sign",
+                "--sec",
+                &sec_file,
+                "--inform",
+                format,
+                "--out",
+                &sig_file,
+                "--outform",
+                format,
+                "--file",
+                &sec_file,
+            ]);
+            assert!(generate.run().is_ok());
+            assert!(public.run().is_ok());
+            assert!(sign.run().is_ok());
+            assert!(fs::read(&sig_file).unwrap().is_ascii());
+
+            let mut args = vec![
+                "verify", "--pub", &pub_file, "--sig", &sig_file, "--file", &sec_file,
+            ];
+            if !detected {
+                args.extend(["--inform", format, "--sigform", format]);
+            }
+            assert!(VerifyCmd::parse_from(&args).run().is_ok());
+        }
+
+        let wrong_encoding = VerifyCmd::parse_from([
+            "verify",
+            "--pub",
+            &pub_file,
+            "--sig",
+            &sig_file,
+            "--sigform",
+            "HEX",
+            "--file",
+            &sec_file,
+        ]);
+        assert!(matches!(
+            wrong_encoding.run(),
+            Err(CryptoError::File { .. })
+        ));
+        cleanup(&[sec_file, pub_file, sig_file]);
+    }
+
+    #[test]
+    fn verify_takes_the_key_from_a_certificate() {
+        let sec_file = "ver_sec_test_cert".to_string();
+        let sig_file = "ver_sig_test_cert".to_string();
+        let root_file = "ver_root_test_cert".to_string();
+        let cert_file = "ver_cert_test_cert".to_string();
+        let crl_file = "ver_crl_test_cert".to_string();
+        let root_key = crypto::generate(crypto::Algorithm::Mldsa65);
+        let mut options = x509::CertificateOptions::new(x509::parse_name("CN=Root").unwrap(), 30);
+        options.extensions.ca = true;
+        let root = x509::self_signed(&root_key, &options).unwrap();
+        let signer_key = crypto::generate(crypto::Algorithm::Mldsa44);
+        let signer = x509::issue(
+            &root_key,
+            &root,
+            &signer_key.public_key(),
+            &x509::CertificateOptions::new(x509::parse_name("CN=Signer").unwrap(), 10),
+        )
+        .unwrap();
+        fs::write(&sec_file, signer_key.to_pem().unwrap()).unwrap();
+        fs::write(&root_file, x509::to_pem(&root).unwrap()).unwrap();
+        fs::write(&cert_file, x509::to_pem(&signer).unwrap()).unwrap();
+        let now = std::time::SystemTime::now();
+        let crl = x509::crl(
+            &root_key,
+            &root,
+            &x509::CrlOptions {
+                this_update: now,
+                next_update: now + std::time::Duration::from_secs(24 * 60 * 60),
+                number: vec![1],
+                revoked: vec![x509::RevokedCertificate {
+                    serial_number: signer.tbs_certificate.serial_number.as_bytes().to_vec(),
+                    time: now,
+                    reason: x509::CrlReason::KeyCompromise,
+                }],
+            },
+        )
+        .unwrap();
+        fs::write(&crl_file, x509::crl_to_pem(&crl).unwrap()).unwrap();
+
+        let sign = SignCmd::parse_from([
+            "sign", "--sec", &sec_file, "--out", &sig_file, "--file", &sec_file,
+        ]);
+        assert!(sign.run().is_ok());
+        let verify = |extra: &[&str]| {
+            let mut args = vec![
+                "verify", "--cert", &cert_file, "--sig", &sig_file, "--file", &sec_file,
+            ];
+            args.extend_from_slice(extra);
+            VerifyCmd::parse_from(args).run()
+        };
+        let untrusted = VerifyCmd::try_parse_from([
+            "verify", "--cert", &cert_file, "--sig", &sig_file, "--file", &sec_file,
+        ]);
+        let trusted = verify(&["--trusted", &root_file]);
+        // A certificate that is itself trusted needs no path
+        let directly_trusted = verify(&["--trusted", &cert_file]);
+        let expired = verify(&["--trusted", &root_file, "--at", "2100-01-01T00:00:00Z"]);
+        let revoked = verify(&["--trusted", &root_file, "--crl", &crl_file]);
+        let both = VerifyCmd::parse_from([
+            "verify",
+            "--cert",
+            &cert_file,
+            "--trusted",
+            &root_file,
+            "--pub",
+            &root_file,
+            "--sig",
+            &sig_file,
+            "--file",
+            &sec_file,
+        ])
+        .run();
+        let trusted_without_cert = VerifyCmd::try_parse_from([
+            "verify",
+            "--pub",
+            &sec_file,
+            "--trusted",
+            &root_file,
+            "--sig",
+            &sig_file,
+            "--file",
+            &sec_file,
+        ]);
+        cleanup(&[sec_file, sig_file, root_file, cert_file, crl_file]);
+
+        assert!(untrusted.is_err());
+        assert!(trusted.is_ok());
+        assert!(directly_trusted.is_ok());
+        assert!(matches!(
+            expired,
+            Err(CryptoError::PathValidation {
+                check: chain::PathCheck::Expired(_),
+                ..
+            })
+        ));
+        assert!(matches!(revoked, Err(CryptoError::Revoked { .. })));
+        assert!(matches!(both, Err(CryptoError::InvalidArgument(_))));
+        assert!(trusted_without_cert.is_err());
+    }
+
     #[test]
     fn verify_with_missing_public_key_returns_io_error() {
         let verify = VerifyCmd::parse_from([
@@ -0,0 +1,134 @@
+//! Composite ML-DSA + Ed25519 signatures (draft-ietf-lamps-pq-composite-sigs). A signature is
+//! valid only if both component signatures are, so it stays secure while either primitive does.
+//!
+//! Both components sign the message representative
+//! `M' = Prefix || Label || len(ctx) || ctx || SHA-512(M)`; ML-DSA signs it with `Label` as its
+//! context string. Keys, key pairs and signatures are the ML-DSA part followed by the Ed25519
+//! part, and the private key is `mldsaSeed || Ed25519 private key`.
+use ed25519_dalek::{Signer, SigningKey, Verifier, VerifyingKey};
+use rand::{thread_rng, RngCore};
+use sha2::{Digest, Sha512};
+
+/// Ed25519 public and private key length
+pub(crate) const ED25519_KEY_LEN: usize = 32;
+pub(crate) const ED25519_SIGNATURE_LEN: usize = 64;
+/// ML-DSA seed followed by the Ed25519 private key
+pub(crate) const PRIVATE_KEY_LEN: usize = 32 + ED25519_KEY_LEN;
+
+/// Domain separation labels of the composite parameter sets
+pub(crate) const LABEL_MLDSA44_ED25519: &[u8] = b"COMPSIG-MLDSA44-Ed25519-SHA512";
+pub(crate) const LABEL_MLDSA65_ED25519: &[u8] = b"COMPSIG-MLDSA65-Ed25519-SHA512";
+
+/// `Prefix`, the ASCII string `CompositeAlgorithmSignatures2025`
+const PREFIX: &[u8] = b"CompositeAlgorithmSignatures2025";
+
+/// `M'` for `message` signed under the composite `label` with the user `context`
+pub(crate) fn message_representative(label: &[u8], context: &[u8], message: &[u8]) -> Vec<u8> {
+    let context_len = u8::try_from(context.len()).expect("context length is checked");
+    [
+        PREFIX,
+        label,
+        &[context_len],
+        context,
+        &Sha512::digest(message),
+    ]
+    .concat()
+}
+
+/// Fresh Ed25519 private key. It is drawn independently of the ML-DSA seed, so that
+/// recovering one component key reveals nothing about the other.
+pub(crate) fn random_ed25519_key() -> [u8; ED25519_KEY_LEN] {
+    let mut key = [0u8; ED25519_KEY_LEN];
+    thread_rng().fill_bytes(&mut key);
+    key
+}
+
+pub(crate) fn ed25519_public_key(private_key: &[u8]) -> [u8; ED25519_KEY_LEN] {
+    signing_key(private_key).verifying_key().to_bytes()
+}
+
+pub(crate) fn ed25519_sign(private_key: &[u8], message: &[u8]) -> [u8; ED25519_SIGNATURE_LEN] {
+    signing_key(private_key).sign(message).to_bytes()
+}
+
+pub(crate) fn ed25519_verify(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    let (Ok(public_key), Ok(signature)) = (
+        <[u8; ED25519_KEY_LEN]>::try_from(public_key),
+        ed25519_dalek::Signature::from_slice(signature),
+    ) else {
+        return false;
+    };
+    VerifyingKey::from_bytes(&public_key)
+        .is_ok_and(|public_key| public_key.verify(message, &signature).is_ok())
+}
+
+fn signing_key(private_key: &[u8]) -> SigningKey {
+    SigningKey::from_bytes(
+        private_key
+            .try_into()
+            .expect("Ed25519 private keys are 32 bytes"),
+    )
+}
+
+/// Key pair bytes in the layout of `AlgorithmSpec`: the composite public key
+/// (ML-DSA, Ed25519) followed by the composite secret key (ML-DSA, Ed25519)
+pub(crate) fn keypair(
+    mldsa_keypair: &[u8],
+    mldsa_public_key_len: usize,
+    ed25519_private_key: &[u8],
+) -> Vec<u8> {
+    let (mldsa_public, mldsa_secret) = mldsa_keypair.split_at(mldsa_public_key_len);
+    [
+        mldsa_public,
+        &ed25519_public_key(ed25519_private_key),
+        mldsa_secret,
+        ed25519_private_key,
+    ]
+    .concat()
+}
+
+/// Split composite key pair bytes into the ML-DSA key pair and the Ed25519 private key
+pub(crate) fn split_keypair(keypair: &[u8], mldsa_public_key_len: usize) -> (Vec<u8>, &[u8]) {
+    let public_key_len = mldsa_public_key_len + ED25519_KEY_LEN;
+    let (public_key, secret_key) = keypair.split_at(public_key_len);
+    let (mldsa_secret, ed25519_private_key) =
+        secret_key.split_at(secret_key.len() - ED25519_KEY_LEN);
+    let mldsa_keypair = [&public_key[..mldsa_public_key_len], mldsa_secret].concat();
+    (mldsa_keypair, ed25519_private_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_representative_layout() {
+        let m = message_representative(b"LABEL", b"ctx", b"message");
+        assert!(m.starts_with(b"CompositeAlgorithmSignatures2025LABEL\x03ctx"));
+        assert_eq!(m.len(), PREFIX.len() + 5 + 1 + 3 + 64);
+    }
+
+    #[test]
+    fn ed25519_rfc8032_test_vector() {
+        // RFC 8032 section 7.1, TEST 1
+        let private_key: Vec<u8> = (0..32)
+            .map(|i| {
+                u8::from_str_radix(
+                    &"9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60"
+                        [2 * i..2 * i + 2],
+                    16,
+                )
+                .unwrap()
+            })
+            .collect();
+        let public_key = ed25519_public_key(&private_key);
+        assert_eq!(
+            public_key[..4],
+            [0xd7, 0x5a, 0x98, 0x01],
+            "public key of the RFC 8032 test key"
+        );
+        let signature = ed25519_sign(&private_key, b"");
+        assert!(ed25519_verify(&public_key, b"", &signature));
+        assert!(!ed25519_verify(&public_key, b"x", &signature));
+    }
+}
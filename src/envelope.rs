@@ -0,0 +1,143 @@
+//! Signature envelope: the signature together with its algorithm, the fingerprint of the
+//! signer's key, the context and an optional signing time, in DER or PEM (`ML-DSA SIGNATURE`).
+use crate::algorithm::Algorithm;
+use crate::armor;
+use crate::asc1_dilithium::{AlgorithmIdentifier, SignatureEnvelopeBorrowed};
+use crate::error::CryptoError;
+use crate::fingerprint::Fingerprint;
+use crate::keys::{PublicKey, Signature};
+use der::asn1::{GeneralizedTime, OctetStringRef};
+use der::pem::PemLabel;
+use der::{Decode, Encode};
+use std::time::SystemTime;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignatureEnvelope {
+    pub signature: Signature,
+    /// Fingerprint of the public key the signature verifies with
+    pub key_fingerprint: Fingerprint,
+    /// FIPS 204 context string the signature was made with
+    pub context: Vec<u8>,
+    pub signing_time: Option<SystemTime>,
+}
+
+impl SignatureEnvelope {
+    pub fn new(
+        signature: Signature,
+        public_key: &PublicKey,
+        context: &[u8],
+    ) -> Result<Self, CryptoError> {
+        Ok(SignatureEnvelope {
+            signature,
+            key_fingerprint: public_key.fingerprint()?,
+            context: context.to_vec(),
+            signing_time: None,
+        })
+    }
+
+    /// Decode a DER `SignatureEnvelope`
+    pub fn from_der(bytes: &[u8]) -> Result<Self, CryptoError> {
+        let envelope =
+            SignatureEnvelopeBorrowed::from_der(bytes).map_err(CryptoError::DerDecode)?;
+        if envelope.pre_hash.is_some() {
+            return Err(CryptoError::UnsupportedOption(
+                "HashML-DSA signatures are not supported".to_string(),
+            ));
+        }
+        let algorithm = Algorithm::from_oid(&envelope.algorithm.algorithm)?;
+        let key_fingerprint = envelope
+            .key_fingerprint
+            .as_bytes()
+            .try_into()
+            .map_err(|_| CryptoError::DerDecode(der::Tag::OctetString.length_error()))?;
+        Ok(SignatureEnvelope {
+            signature: Signature::from_bytes(algorithm, envelope.signature)?,
+            key_fingerprint: Fingerprint(key_fingerprint),
+            context: envelope
+                .context
+                .map(|context| context.as_bytes().to_vec())
+                .unwrap_or_default(),
+            signing_time: envelope
+                .signing_time
+                .map(|signing_time| signing_time.to_system_time()),
+        })
+    }
+
+    /// Decode a PEM `ML-DSA SIGNATURE`
+    pub fn from_pem(bytes: &[u8]) -> Result<Self, CryptoError> {
+        let (label, der) = armor::decode(bytes)?;
+        SignatureEnvelopeBorrowed::validate_pem_label(&label)
+            .map_err(|err| CryptoError::PemDecode(err.into()))?;
+        Self::from_der(&der)
+    }
+
+    /// Decode a PEM or DER `SignatureEnvelope`
+    pub fn decode(bytes: &[u8]) -> Result<Self, CryptoError> {
+        if bytes.trim_ascii_start().starts_with(b"-----BEGIN ") {
+            Self::from_pem(bytes)
+        } else {
+            Self::from_der(bytes)
+        }
+    }
+
+    pub fn to_der(&self) -> Result<Vec<u8>, CryptoError> {
+        let signing_time = self
+            .signing_time
+            .map(GeneralizedTime::from_system_time)
+            .transpose()?;
+        let context = (!self.context.is_empty())
+            .then(|| OctetStringRef::new(&self.context))
+            .transpose()?;
+        let envelope = SignatureEnvelopeBorrowed {
+            version: 0,
+            algorithm: AlgorithmIdentifier {
+                algorithm: self.signature.algorithm().oid(),
+            },
+            key_fingerprint: OctetStringRef::new(self.key_fingerprint.as_bytes())?,
+            context,
+            pre_hash: None,
+            signing_time,
+            signature: self.signature.as_bytes(),
+        };
+        Ok(envelope.to_der()?)
+    }
+
+    pub fn to_pem(&self) -> Result<String, CryptoError> {
+        Ok(armor::encode(
+            SignatureEnvelopeBorrowed::PEM_LABEL,
+            &self.to_der()?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::SecretKey;
+    use std::time::Duration;
+
+    #[test]
+    fn envelope_round_trip() {
+        let secret = SecretKey::from_seed(Algorithm::Mldsa65, &[7u8; 32]);
+        let signature = secret.sign(b"message").unwrap();
+        let mut envelope =
+            SignatureEnvelope::new(signature, &secret.public_key(), b"governance").unwrap();
+        envelope.signing_time = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+
+        let pem = envelope.to_pem().unwrap();
+        assert!(pem.starts_with("-----BEGIN ML-DSA SIGNATURE-----"));
+        assert_eq!(SignatureEnvelope::decode(pem.as_bytes()).unwrap(), envelope);
+        assert_eq!(
+            SignatureEnvelope::decode(&envelope.to_der().unwrap()).unwrap(),
+            envelope
+        );
+    }
+
+    #[test]
+    fn raw_signature_is_not_an_envelope() {
+        let signature = SecretKey::from_seed(Algorithm::Mldsa44, &[7u8; 32])
+            .sign(b"message")
+            .unwrap();
+        assert!(SignatureEnvelope::decode(signature.as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,92 @@
+#[derive(Debug, thiserror::Error)]
+pub enum CryptoError {
+    #[error("Request to QRNG failed: {0}")]
+    RequestQrngError(String),
+    #[error("The application does not support this algorithm: {0}")]
+    InvalidAlgorithm(String),
+    #[error("The application does not support this format: {0}")]
+    InvalidFormat(String),
+    #[error("Unknown algorithm OID: {0}")]
+    UnknownAlgorithmOid(String),
+    #[error("Failed to decode DER: {0}")]
+    DerDecode(der::Error),
+    #[error("Failed to decode PEM: {0}")]
+    PemDecode(der::Error),
+    #[error("Invalid {kind} key length for {algorithm}: expected {expected} bytes, got {actual}")]
+    KeySizeMismatch {
+        kind: &'static str,
+        algorithm: String,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("Invalid signature length for {algorithm}: expected {expected} bytes, got {actual}")]
+    SignatureSizeMismatch {
+        algorithm: String,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("The private key has no seed, it can only be written in expanded form")]
+    MissingSeed,
+    #[error("An expandedKey private key needs the public key, write it as a v2 key or use the seed or both form")]
+    MissingPublicKey,
+    #[error("Inconsistent key: {0}")]
+    KeyMismatch(String),
+    #[error("Private key encryption error: {0}")]
+    Encryption(pkcs8::Error),
+    #[error("The private key is encrypted, a password is required")]
+    MissingPassword,
+    #[error("Invalid password source: {0}")]
+    InvalidPassword(String),
+    /// Any of the above, raised while reading `path`
+    #[error("{path}: {source}")]
+    File {
+        path: String,
+        source: Box<CryptoError>,
+    },
+    #[error("No key matching {0}")]
+    KeyNotFound(String),
+    #[error("Signature verification failed")]
+    VerificationFailed,
+    #[error("Invalid context: {0}")]
+    InvalidContext(String),
+    #[error("Unsupported option: {0}")]
+    UnsupportedOption(String),
+    #[error("Signing with {0} failed")]
+    SigningFailed(String),
+    #[error("Invalid CMS message: {0}")]
+    InvalidCms(String),
+    #[error("Invalid certificate: {0}")]
+    InvalidCertificate(String),
+    #[error("Certificate path validation failed at {subject}: {check}")]
+    PathValidation {
+        subject: String,
+        check: crate::chain::PathCheck,
+    },
+    #[error("Certificate {subject} with serial number {serial} was revoked at {time} ({reason})")]
+    Revoked {
+        subject: String,
+        serial: String,
+        time: String,
+        reason: String,
+    },
+    #[error("Certificate authority: {0}")]
+    CertificateAuthority(String),
+    #[error("Invalid manifest: {0}")]
+    InvalidManifest(String),
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error(transparent)]
+    Der(#[from] der::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+impl CryptoError {
+    /// Attach the path of the file whose contents caused the error
+    pub fn in_file(self, path: &str) -> CryptoError {
+        CryptoError::File {
+            path: path.to_string(),
+            source: Box::new(self),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Stable identifiers for public keys: the SHA-256 fingerprint of the DER
+//! `SubjectPublicKeyInfo`, and a short key ID taken from its first bytes.
+use base64::{engine::general_purpose, Engine as _};
+use std::fmt;
+
+/// Number of fingerprint bytes that make up the key ID
+pub const KEY_ID_LEN: usize = 8;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Fingerprint(pub [u8; 32]);
+
+impl Fingerprint {
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Lowercase hex of the whole digest
+    pub fn to_hex(&self) -> String {
+        hex(&self.0)
+    }
+
+    /// Unpadded base64 of the whole digest, as printed by `ssh-keygen -l`
+    pub fn to_base64(&self) -> String {
+        general_purpose::STANDARD_NO_PAD.encode(self.0)
+    }
+
+    /// Lowercase hex of the first [`KEY_ID_LEN`] bytes
+    pub fn key_id(&self) -> String {
+        hex(&self.0[..KEY_ID_LEN])
+    }
+
+    /// Whether `id` names this key: the key ID, or the fingerprint in hex or base64,
+    /// optionally prefixed with `SHA256:`. Hex is compared case-insensitively.
+    pub fn matches(&self, id: &str) -> bool {
+        let id = id.trim();
+        let id = id.strip_prefix("SHA256:").unwrap_or(id);
+        id.eq_ignore_ascii_case(&self.key_id())
+            || id.eq_ignore_ascii_case(&self.to_hex())
+            || id.trim_end_matches('=') == self.to_base64()
+    }
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SHA256:{}", self.to_hex())
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprint_matches_every_representation() {
+        let fingerprint = Fingerprint([0xab; 32]);
+        assert_eq!(fingerprint.key_id(), "abababababababab");
+        assert!(fingerprint.matches("ABABABABABABABAB"));
+        assert!(fingerprint.matches(&fingerprint.to_string()));
+        assert!(fingerprint.matches(&fingerprint.to_hex()));
+        assert!(fingerprint.matches(&format!("SHA256:{}=", fingerprint.to_base64())));
+        assert!(!fingerprint.matches("abab"));
+        assert!(!Fingerprint([0xcd; 32]).matches(&fingerprint.key_id()));
+    }
+}
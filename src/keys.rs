@@ -0,0 +1,901 @@
+use crate::algorithm::Algorithm;
+use crate::asc1_dilithium::{
+    AlgorithmIdentifier, MlDsaPrivateKey, MlDsaPrivateKeyBoth, OneAsymmetricKeyBorrowed,
+    OneAsymmetricKeyOwned, SubjectPublicKeyInfoBorrowed, SubjectPublicKeyInfoOwned,
+};
+use crate::composite;
+use crate::error::CryptoError;
+use crate::fingerprint::Fingerprint;
+use der::asn1::{BitStringRef, OctetStringRef};
+use der::pem::{self, LineEnding, PemLabel};
+use der::{Decode, DecodePem, Encode, EncodePem, Header, Reader, SliceReader, Tag};
+use pkcs8::EncryptedPrivateKeyInfo;
+use rand::{thread_rng, RngCore};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::str::FromStr;
+
+/// Private key: the algorithm, the full key pair bytes (public key followed by secret key)
+/// and, when known, the 32-byte seed they were derived from
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretKey {
+    algorithm: Algorithm,
+    seed: Option<[u8; 32]>,
+    keypair: Vec<u8>,
+}
+
+/// Parameters of [`SecretKey::sign_with`]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct SignOptions<'a> {
+    /// FIPS 204 context string, empty by default. ML-DSA only
+    pub context: &'a [u8],
+    /// Hedged (randomized) instead of deterministic signing. ML-DSA only
+    pub hedged: bool,
+}
+
+/// Encoding of the private key inside `OneAsymmetricKey` (RFC 9881)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PrivateKeyFormat {
+    /// The 32-byte seed only
+    Seed,
+    /// The expanded secret key only
+    Expanded,
+    /// Seed and expanded secret key
+    Both,
+}
+
+/// How [`SecretKey`] is written out
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct KeyEncoding {
+    pub format: PrivateKeyFormat,
+    /// Write a v2 `OneAsymmetricKey` that carries the public key. The expanded format always
+    /// does, as the key cannot be read back without it.
+    pub with_public_key: bool,
+}
+
+impl KeyEncoding {
+    fn writes_public_key(&self) -> bool {
+        self.with_public_key || self.format == PrivateKeyFormat::Expanded
+    }
+}
+
+impl From<PrivateKeyFormat> for KeyEncoding {
+    fn from(format: PrivateKeyFormat) -> Self {
+        KeyEncoding {
+            format,
+            with_public_key: false,
+        }
+    }
+}
+
+impl FromStr for PrivateKeyFormat {
+    type Err = CryptoError;
+
+    fn from_str(s: &str) -> Result<Self, CryptoError> {
+        match s.to_ascii_lowercase().as_str() {
+            "seed" => Ok(PrivateKeyFormat::Seed),
+            "expanded" => Ok(PrivateKeyFormat::Expanded),
+            "both" => Ok(PrivateKeyFormat::Both),
+            _ => Err(CryptoError::InvalidFormat(s.to_string())),
+        }
+    }
+}
+
+/// Raw public key bytes tagged with their algorithm
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicKey {
+    algorithm: Algorithm,
+    bytes: Vec<u8>,
+}
+
+/// Raw signature bytes tagged with the algorithm that produced them
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Signature {
+    algorithm: Algorithm,
+    bytes: Vec<u8>,
+}
+
+/// Generate a key pair from a random seed
+pub fn generate(algorithm: Algorithm) -> SecretKey {
+    let mut seed = [0u8; 32];
+    thread_rng().fill_bytes(&mut seed[..]);
+    SecretKey::from_seed(algorithm, &seed)
+}
+
+impl SecretKey {
+    /// Key pair from a 32-byte seed, fully determined by the seed except for composite
+    /// parameter sets: their seed is the ML-DSA seed, and the Ed25519 key is generated
+    /// independently, as the composite draft specifies.
+    pub fn from_seed(algorithm: Algorithm, seed: &[u8; 32]) -> Self {
+        SecretKey {
+            algorithm,
+            seed: Some(*seed),
+            keypair: (algorithm.spec().generate)(seed),
+        }
+    }
+
+    /// Build a key from raw key pair bytes, checking the length for the algorithm
+    pub fn from_bytes(algorithm: Algorithm, keypair: &[u8]) -> Result<Self, CryptoError> {
+        let expected = algorithm.spec().keypair_len();
+        if keypair.len() != expected {
+            return Err(CryptoError::KeySizeMismatch {
+                kind: "private",
+                algorithm: algorithm.to_string(),
+                expected,
+                actual: keypair.len(),
+            });
+        }
+        Ok(SecretKey {
+            algorithm,
+            seed: None,
+            keypair: keypair.to_vec(),
+        })
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    pub fn seed(&self) -> Option<&[u8; 32]> {
+        self.seed.as_ref()
+    }
+
+    /// The expanded secret key, without the public key
+    pub fn expanded_key(&self) -> &[u8] {
+        &self.keypair[self.algorithm.spec().public_key_len..]
+    }
+
+    /// Decode a DER `OneAsymmetricKey`
+    pub fn from_der(bytes: &[u8]) -> Result<Self, CryptoError> {
+        let one_asymmetric_key =
+            OneAsymmetricKeyBorrowed::from_der(bytes).map_err(CryptoError::DerDecode)?;
+        let algorithm = Algorithm::from_oid(&one_asymmetric_key.private_key_algorithm.algorithm)?;
+        let public_key = one_asymmetric_key
+            .public_key
+            .as_ref()
+            .map(|public_key| public_key.raw_bytes());
+        Self::from_private_key(algorithm, one_asymmetric_key.private_key, public_key)
+    }
+
+    /// Decode a PEM `PRIVATE KEY`
+    pub fn from_pem(bytes: &[u8]) -> Result<Self, CryptoError> {
+        let one_asymmetric_key =
+            OneAsymmetricKeyOwned::from_pem(bytes).map_err(CryptoError::PemDecode)?;
+        let algorithm = Algorithm::from_oid(&one_asymmetric_key.private_key_algorithm.algorithm)?;
+        let public_key = one_asymmetric_key
+            .public_key
+            .as_ref()
+            .map(|public_key| public_key.raw_bytes());
+        Self::from_private_key(
+            algorithm,
+            one_asymmetric_key.private_key.as_bytes(),
+            public_key,
+        )
+    }
+
+    /// Decode a private key in PEM or DER, telling apart plain and encrypted keys.
+    /// `password` is only used for an `EncryptedPrivateKeyInfo`
+    pub fn decode(bytes: &[u8], password: Option<&[u8]>) -> Result<Self, CryptoError> {
+        let pem = is_pem(bytes);
+        let encrypted = if pem {
+            pem::decode_label(bytes).map_err(|err| CryptoError::PemDecode(err.into()))?
+                == EncryptedPrivateKeyInfo::PEM_LABEL
+        } else {
+            is_encrypted_der(bytes)
+        };
+        match (encrypted, pem) {
+            (false, false) => Self::from_der(bytes),
+            (false, true) => Self::from_pem(bytes),
+            (true, false) => {
+                Self::from_encrypted_der(bytes, password.ok_or(CryptoError::MissingPassword)?)
+            }
+            (true, true) => {
+                Self::from_encrypted_pem(bytes, password.ok_or(CryptoError::MissingPassword)?)
+            }
+        }
+    }
+
+    /// Decode the contents of the `privateKey` OCTET STRING, checking it against the
+    /// `publicKey` field of a v2 key if there is one
+    fn from_private_key(
+        algorithm: Algorithm,
+        private_key: &[u8],
+        public_key: Option<&[u8]>,
+    ) -> Result<Self, CryptoError> {
+        let spec = algorithm.spec();
+        let secret_key = match spec.composite {
+            Some(mldsa) => Self::from_composite_private_key(algorithm, mldsa, private_key)?,
+            None => Self::from_mldsa_private_key(algorithm, private_key, public_key)?,
+        };
+        if let Some(public_key) = public_key {
+            if public_key != secret_key.public_key().as_bytes() {
+                return Err(CryptoError::KeyMismatch(
+                    "publicKey does not match the private key".to_string(),
+                ));
+            }
+        }
+        Ok(secret_key)
+    }
+
+    /// Decode an `ML-DSA-PrivateKey` CHOICE
+    fn from_mldsa_private_key(
+        algorithm: Algorithm,
+        private_key: &[u8],
+        public_key: Option<&[u8]>,
+    ) -> Result<Self, CryptoError> {
+        let spec = algorithm.spec();
+        Ok(
+            match MlDsaPrivateKey::from_der(private_key).map_err(CryptoError::DerDecode)? {
+                MlDsaPrivateKey::Seed(seed) => {
+                    Self::from_seed(algorithm, &seed_bytes(algorithm, seed)?)
+                }
+                // Earlier versions of this tool stored the whole key pair
+                MlDsaPrivateKey::ExpandedKey(key) if key.as_bytes().len() == spec.keypair_len() => {
+                    Self::from_bytes(algorithm, key.as_bytes())?
+                }
+                MlDsaPrivateKey::ExpandedKey(key)
+                    if key.as_bytes().len() == spec.secret_key_len =>
+                {
+                    let public_key = public_key.ok_or(CryptoError::MissingPublicKey)?;
+                    let public_key = PublicKey::from_bytes(algorithm, public_key)?;
+                    let secret_key = Self::from_bytes(
+                        algorithm,
+                        &[public_key.as_bytes(), key.as_bytes()].concat(),
+                    )?;
+                    // The public key cannot be recomputed from the expanded key alone, so check
+                    // that the two halves belong together by signing with them
+                    let signature = secret_key.sign(b"key pair consistency check")?;
+                    if !public_key.verify(b"key pair consistency check", &signature)? {
+                        return Err(CryptoError::KeyMismatch(
+                            "publicKey does not match the private key".to_string(),
+                        ));
+                    }
+                    return Ok(secret_key);
+                }
+                MlDsaPrivateKey::ExpandedKey(key) => {
+                    return Err(CryptoError::KeySizeMismatch {
+                        kind: "private",
+                        algorithm: algorithm.to_string(),
+                        expected: spec.secret_key_len,
+                        actual: key.as_bytes().len(),
+                    })
+                }
+                MlDsaPrivateKey::Both(both) => {
+                    let secret_key = Self::from_seed(algorithm, &seed_bytes(algorithm, both.seed)?);
+                    if secret_key.expanded_key() != both.expanded_key.as_bytes() {
+                        return Err(CryptoError::KeyMismatch(
+                            "expandedKey does not match the key derived from seed".to_string(),
+                        ));
+                    }
+                    secret_key
+                }
+            },
+        )
+    }
+
+    /// Decode a composite private key, the ML-DSA seed followed by the Ed25519 private key
+    fn from_composite_private_key(
+        algorithm: Algorithm,
+        mldsa: Algorithm,
+        private_key: &[u8],
+    ) -> Result<Self, CryptoError> {
+        if private_key.len() != composite::PRIVATE_KEY_LEN {
+            return Err(CryptoError::KeySizeMismatch {
+                kind: "private",
+                algorithm: algorithm.to_string(),
+                expected: composite::PRIVATE_KEY_LEN,
+                actual: private_key.len(),
+            });
+        }
+        let (seed, ed25519_key) = private_key.split_at(32);
+        let seed: [u8; 32] = seed.try_into().expect("split at 32 bytes");
+        let mldsa_spec = mldsa.spec();
+        Ok(SecretKey {
+            algorithm,
+            seed: Some(seed),
+            keypair: composite::keypair(
+                &(mldsa_spec.generate)(&seed),
+                mldsa_spec.public_key_len,
+                ed25519_key,
+            ),
+        })
+    }
+
+    /// Seed and expanded key when the seed is known, the expanded key otherwise. Composite
+    /// keys are always stored as seeds.
+    pub fn default_format(&self) -> PrivateKeyFormat {
+        if self.algorithm.spec().composite.is_some() {
+            PrivateKeyFormat::Seed
+        } else if self.seed.is_some() {
+            PrivateKeyFormat::Both
+        } else {
+            PrivateKeyFormat::Expanded
+        }
+    }
+
+    /// Encode as DER `OneAsymmetricKey` in the default private key format
+    pub fn to_der(&self) -> Result<Vec<u8>, CryptoError> {
+        self.to_der_as(self.default_format().into())
+    }
+
+    pub fn to_pem(&self) -> Result<String, CryptoError> {
+        self.to_pem_as(self.default_format().into())
+    }
+
+    /// Encode as DER `OneAsymmetricKey` with the given encoding
+    pub fn to_der_as(&self, encoding: KeyEncoding) -> Result<Vec<u8>, CryptoError> {
+        let private_key = self.private_key(encoding.format)?;
+        let public_key = self.public_key();
+        Ok(self
+            .one_asymmetric_key(
+                &private_key,
+                encoding.writes_public_key().then_some(&public_key),
+            )?
+            .to_der()?)
+    }
+
+    pub fn to_pem_as(&self, encoding: KeyEncoding) -> Result<String, CryptoError> {
+        let private_key = self.private_key(encoding.format)?;
+        let public_key = self.public_key();
+        Ok(self
+            .one_asymmetric_key(
+                &private_key,
+                encoding.writes_public_key().then_some(&public_key),
+            )?
+            .to_pem(LineEnding::LF)?)
+    }
+
+    /// DER of the `privateKey` OCTET STRING contents, or for composite keys the raw
+    /// `mldsaSeed || Ed25519 private key`, which has no expanded form
+    fn private_key(&self, format: PrivateKeyFormat) -> Result<Vec<u8>, CryptoError> {
+        if self.algorithm.spec().composite.is_some() {
+            return match (format, &self.seed) {
+                (PrivateKeyFormat::Expanded, _) => Err(CryptoError::UnsupportedOption(format!(
+                    "{} keys are only stored as seeds",
+                    self.algorithm
+                ))),
+                (_, Some(seed)) => {
+                    let ed25519_key =
+                        &self.keypair[self.keypair.len() - composite::ED25519_KEY_LEN..];
+                    Ok([&seed[..], ed25519_key].concat())
+                }
+                (_, None) => Err(CryptoError::MissingSeed),
+            };
+        }
+        let expanded_key = OctetStringRef::new(self.expanded_key())?;
+        let private_key = match (format, &self.seed) {
+            (PrivateKeyFormat::Expanded, _) => MlDsaPrivateKey::ExpandedKey(expanded_key),
+            (PrivateKeyFormat::Seed, Some(seed)) => {
+                MlDsaPrivateKey::Seed(OctetStringRef::new(seed)?)
+            }
+            (PrivateKeyFormat::Both, Some(seed)) => MlDsaPrivateKey::Both(MlDsaPrivateKeyBoth {
+                seed: OctetStringRef::new(seed)?,
+                expanded_key,
+            }),
+            (_, None) => return Err(CryptoError::MissingSeed),
+        };
+        Ok(private_key.to_der()?)
+    }
+
+    fn one_asymmetric_key<'a>(
+        &self,
+        private_key: &'a [u8],
+        public_key: Option<&'a PublicKey>,
+    ) -> Result<OneAsymmetricKeyBorrowed<'a>, CryptoError> {
+        Ok(OneAsymmetricKeyBorrowed {
+            version: if public_key.is_some() { 1 } else { 0 },
+            private_key_algorithm: AlgorithmIdentifier {
+                algorithm: self.algorithm.oid(),
+            },
+            private_key,
+            attributes: None,
+            public_key: public_key
+                .map(|public_key| BitStringRef::from_bytes(public_key.as_bytes()))
+                .transpose()?,
+        })
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey {
+            algorithm: self.algorithm,
+            bytes: (self.algorithm.spec().public_key)(&self.keypair),
+        }
+    }
+
+    pub fn sign(&self, message: &[u8]) -> Result<Signature, CryptoError> {
+        self.sign_with(message, &SignOptions::default())
+    }
+
+    pub fn sign_with(
+        &self,
+        message: &[u8],
+        options: &SignOptions<'_>,
+    ) -> Result<Signature, CryptoError> {
+        self.algorithm.check_context(options.context)?;
+        if options.hedged && !self.algorithm.spec().hedged {
+            return Err(CryptoError::UnsupportedOption(format!(
+                "{} only supports deterministic signing",
+                self.algorithm
+            )));
+        }
+        let bytes =
+            (self.algorithm.spec().sign)(&self.keypair, message, options.context, options.hedged)
+                .ok_or_else(|| CryptoError::SigningFailed(self.algorithm.to_string()))?;
+        Ok(Signature {
+            algorithm: self.algorithm,
+            bytes,
+        })
+    }
+}
+
+/// PEM starts with its armor, DER with a SEQUENCE tag
+fn is_pem(bytes: &[u8]) -> bool {
+    bytes.trim_ascii_start().starts_with(b"-----BEGIN ")
+}
+
+/// `EncryptedPrivateKeyInfo` opens with an `AlgorithmIdentifier` SEQUENCE, `OneAsymmetricKey`
+/// with its INTEGER version
+fn is_encrypted_der(bytes: &[u8]) -> bool {
+    let tag = SliceReader::new(bytes).and_then(|mut reader| {
+        Header::decode(&mut reader)?;
+        reader.peek_tag()
+    });
+    tag == Ok(Tag::Sequence)
+}
+
+fn seed_bytes(algorithm: Algorithm, seed: OctetStringRef<'_>) -> Result<[u8; 32], CryptoError> {
+    seed.as_bytes()
+        .try_into()
+        .map_err(|_| CryptoError::KeySizeMismatch {
+            kind: "seed",
+            algorithm: algorithm.to_string(),
+            expected: 32,
+            actual: seed.as_bytes().len(),
+        })
+}
+
+impl std::fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretKey")
+            .field("algorithm", &self.algorithm)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PublicKey {
+    /// Build a key from raw public key bytes, checking the length for the algorithm
+    pub fn from_bytes(algorithm: Algorithm, bytes: &[u8]) -> Result<Self, CryptoError> {
+        let spec = algorithm.spec();
+        if bytes.len() != spec.public_key_len {
+            return Err(CryptoError::KeySizeMismatch {
+                kind: "public",
+                algorithm: algorithm.to_string(),
+                expected: spec.public_key_len,
+                actual: bytes.len(),
+            });
+        }
+        Ok(PublicKey {
+            algorithm,
+            bytes: bytes.to_vec(),
+        })
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Decode a `SubjectPublicKeyInfo` in PEM or DER
+    pub fn decode(bytes: &[u8]) -> Result<Self, CryptoError> {
+        if is_pem(bytes) {
+            Self::from_pem(bytes)
+        } else {
+            Self::from_der(bytes)
+        }
+    }
+
+    /// Decode a DER `SubjectPublicKeyInfo`
+    pub fn from_der(bytes: &[u8]) -> Result<Self, CryptoError> {
+        let public_key =
+            SubjectPublicKeyInfoBorrowed::from_der(bytes).map_err(CryptoError::DerDecode)?;
+        let algorithm = Algorithm::from_oid(&public_key.algorithm.algorithm)?;
+        Self::from_bytes(algorithm, public_key.subject_public_key)
+    }
+
+    /// Decode a PEM `PUBLIC KEY`
+    pub fn from_pem(bytes: &[u8]) -> Result<Self, CryptoError> {
+        let public_key =
+            SubjectPublicKeyInfoOwned::from_pem(bytes).map_err(CryptoError::PemDecode)?;
+        let algorithm = Algorithm::from_oid(&public_key.algorithm.algorithm)?;
+        let raw = public_key
+            .subject_public_key
+            .as_bytes()
+            .ok_or(CryptoError::DerDecode(der::Tag::BitString.value_error()))?;
+        Self::from_bytes(algorithm, raw)
+    }
+
+    /// Encode as DER `SubjectPublicKeyInfo`
+    pub fn to_der(&self) -> Result<Vec<u8>, CryptoError> {
+        Ok(self.spki().to_der()?)
+    }
+
+    pub fn to_pem(&self) -> Result<String, CryptoError> {
+        Ok(self.spki().to_pem(LineEnding::LF)?)
+    }
+
+    /// SHA-256 over the DER `SubjectPublicKeyInfo`
+    pub fn fingerprint(&self) -> Result<Fingerprint, CryptoError> {
+        Ok(Fingerprint(Sha256::digest(self.to_der()?).into()))
+    }
+
+    /// Subject key identifier: the SHA-1 of the subjectPublicKey bits (RFC 5280 section
+    /// 4.2.1.2, method 1), as OpenSSL computes it
+    pub fn subject_key_identifier(&self) -> [u8; 20] {
+        Sha1::digest(&self.bytes).into()
+    }
+
+    fn spki(&self) -> SubjectPublicKeyInfoBorrowed<'_> {
+        SubjectPublicKeyInfoBorrowed {
+            algorithm: AlgorithmIdentifier {
+                algorithm: self.algorithm.oid(),
+            },
+            subject_public_key: &self.bytes,
+        }
+    }
+
+    /// Check `signature` over `message`; `Ok(false)` means the signature is invalid
+    pub fn verify(&self, message: &[u8], signature: &Signature) -> Result<bool, CryptoError> {
+        self.verify_with_context(message, signature, &[])
+    }
+
+    /// Check `signature` over `message` made with the FIPS 204 context string `context`
+    pub fn verify_with_context(
+        &self,
+        message: &[u8],
+        signature: &Signature,
+        context: &[u8],
+    ) -> Result<bool, CryptoError> {
+        if signature.algorithm != self.algorithm {
+            return Err(CryptoError::InvalidAlgorithm(
+                signature.algorithm.to_string(),
+            ));
+        }
+        self.algorithm.check_context(context)?;
+        Ok((self.algorithm.spec().verify)(
+            &self.bytes,
+            message,
+            signature.as_bytes(),
+            context,
+        ))
+    }
+}
+
+impl Signature {
+    /// Wrap raw signature bytes, checking the length for the algorithm
+    pub fn from_bytes(algorithm: Algorithm, bytes: &[u8]) -> Result<Self, CryptoError> {
+        let expected = algorithm.spec().signature_len;
+        if bytes.len() != expected {
+            return Err(CryptoError::SignatureSizeMismatch {
+                algorithm: algorithm.to_string(),
+                expected,
+                actual: bytes.len(),
+            });
+        }
+        Ok(Signature {
+            algorithm,
+            bytes: bytes.to_vec(),
+        })
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::ALGORITHMS;
+
+    #[test]
+    fn der_and_pem_round_trip() {
+        for spec in ALGORITHMS.iter() {
+            let secret = generate(spec.algorithm);
+            let public = secret.public_key();
+
+            let from_der = SecretKey::from_der(&secret.to_der().unwrap()).unwrap();
+            let from_pem = SecretKey::from_pem(secret.to_pem().unwrap().as_bytes()).unwrap();
+            assert!(from_der == secret && from_pem == secret);
+
+            assert_eq!(
+                PublicKey::from_der(&public.to_der().unwrap()).unwrap(),
+                public
+            );
+            assert_eq!(
+                PublicKey::from_pem(public.to_pem().unwrap().as_bytes()).unwrap(),
+                public
+            );
+        }
+    }
+
+    #[test]
+    fn private_key_formats() {
+        let secret = SecretKey::from_seed(Algorithm::Mldsa65, &[7u8; 32]);
+        for format in [PrivateKeyFormat::Seed, PrivateKeyFormat::Both] {
+            let decoded = SecretKey::from_der(&secret.to_der_as(format.into()).unwrap()).unwrap();
+            assert!(decoded == secret);
+        }
+
+        // The expanded form always carries the public key, as it cannot be decoded without
+        let expanded = secret.to_der_as(PrivateKeyFormat::Expanded.into()).unwrap();
+        assert_eq!(
+            OneAsymmetricKeyBorrowed::from_der(&expanded)
+                .unwrap()
+                .version,
+            1
+        );
+        assert!(SecretKey::from_der(&expanded).unwrap().public_key() == secret.public_key());
+        let private_key = secret.private_key(PrivateKeyFormat::Expanded).unwrap();
+        let bare = secret
+            .one_asymmetric_key(&private_key, None)
+            .unwrap()
+            .to_der()
+            .unwrap();
+        assert!(matches!(
+            SecretKey::from_der(&bare),
+            Err(CryptoError::MissingPublicKey)
+        ));
+
+        // Legacy key pair blobs still load, but have no seed to write out
+        let legacy = SecretKey::from_bytes(Algorithm::Mldsa65, &secret.keypair).unwrap();
+        assert_eq!(legacy.default_format(), PrivateKeyFormat::Expanded);
+        assert!(matches!(
+            legacy.to_der_as(PrivateKeyFormat::Seed.into()),
+            Err(CryptoError::MissingSeed)
+        ));
+
+        let other = SecretKey::from_seed(Algorithm::Mldsa65, &[8u8; 32]);
+        let mismatched = MlDsaPrivateKey::Both(MlDsaPrivateKeyBoth {
+            seed: OctetStringRef::new(&[7u8; 32]).unwrap(),
+            expanded_key: OctetStringRef::new(other.expanded_key()).unwrap(),
+        })
+        .to_der()
+        .unwrap();
+        assert!(matches!(
+            SecretKey::from_der(
+                &secret
+                    .one_asymmetric_key(&mismatched, None)
+                    .unwrap()
+                    .to_der()
+                    .unwrap()
+            ),
+            Err(CryptoError::KeyMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn v2_keys_carry_the_public_key() {
+        let secret = SecretKey::from_seed(Algorithm::Mldsa44, &[7u8; 32]);
+        for format in [
+            PrivateKeyFormat::Seed,
+            PrivateKeyFormat::Expanded,
+            PrivateKeyFormat::Both,
+        ] {
+            let encoding = KeyEncoding {
+                format,
+                with_public_key: true,
+            };
+            let der = secret.to_der_as(encoding).unwrap();
+            let one_asymmetric_key = OneAsymmetricKeyBorrowed::from_der(&der).unwrap();
+            assert_eq!(one_asymmetric_key.version, 1);
+
+            let decoded = SecretKey::from_der(&der).unwrap();
+            assert!(decoded.public_key() == secret.public_key());
+            let pem = secret.to_pem_as(encoding).unwrap();
+            assert!(
+                SecretKey::from_pem(pem.as_bytes()).unwrap().public_key() == secret.public_key()
+            );
+        }
+
+        // A publicKey field that belongs to another key is rejected
+        let other = SecretKey::from_seed(Algorithm::Mldsa44, &[8u8; 32]).public_key();
+        for format in [PrivateKeyFormat::Seed, PrivateKeyFormat::Both] {
+            let private_key = secret.private_key(format).unwrap();
+            let der = secret
+                .one_asymmetric_key(&private_key, Some(&other))
+                .unwrap()
+                .to_der()
+                .unwrap();
+            assert!(matches!(
+                SecretKey::from_der(&der),
+                Err(CryptoError::KeyMismatch(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn decode_detects_the_encoding() {
+        let secret = generate(Algorithm::Mldsa44);
+        let public = secret.public_key();
+        let kdf = crate::Kdf::Pbkdf2 { iterations: 1000 };
+        let format = PrivateKeyFormat::Seed.into();
+
+        let plain = [
+            secret.to_der().unwrap(),
+            secret.to_pem().unwrap().into_bytes(),
+        ];
+        for bytes in plain {
+            assert!(SecretKey::decode(&bytes, None).unwrap() == secret);
+        }
+        let encrypted = [
+            secret.to_encrypted_der(format, b"secret", kdf).unwrap(),
+            secret
+                .to_encrypted_pem(format, b"secret", kdf)
+                .unwrap()
+                .into_bytes(),
+        ];
+        for bytes in encrypted {
+            assert!(SecretKey::decode(&bytes, Some(b"secret")).unwrap() == secret);
+            assert!(matches!(
+                SecretKey::decode(&bytes, None),
+                Err(CryptoError::MissingPassword)
+            ));
+        }
+
+        assert_eq!(
+            PublicKey::decode(&public.to_der().unwrap()).unwrap(),
+            public
+        );
+        assert_eq!(
+            PublicKey::decode(public.to_pem().unwrap().as_bytes()).unwrap(),
+            public
+        );
+    }
+
+    #[test]
+    fn sign_and_verify() {
+        for spec in ALGORITHMS.iter() {
+            let secret = SecretKey::from_seed(spec.algorithm, &[7u8; 32]);
+            let signature = secret.sign(b"message").unwrap();
+            let public = secret.public_key();
+
+            assert!(public.verify(b"message", &signature).unwrap());
+            assert!(!public.verify(b"other message", &signature).unwrap());
+        }
+    }
+
+    #[test]
+    fn composite_needs_both_components() {
+        let secret = generate(Algorithm::Mldsa65Ed25519);
+        let public = secret.public_key();
+        let signature = secret.sign(b"message").unwrap();
+        assert!(public.verify(b"message", &signature).unwrap());
+
+        // Flipping a byte in either component signature invalidates the whole
+        let mldsa_len = Algorithm::Mldsa65.spec().signature_len;
+        for index in [0, mldsa_len] {
+            let mut bytes = signature.as_bytes().to_vec();
+            bytes[index] ^= 1;
+            let tampered = Signature::from_bytes(Algorithm::Mldsa65Ed25519, &bytes).unwrap();
+            assert!(!public.verify(b"message", &tampered).unwrap());
+        }
+
+        // The ML-DSA component is not a valid standalone ML-DSA signature of the message
+        let component =
+            Signature::from_bytes(Algorithm::Mldsa65, &signature.as_bytes()[..mldsa_len]).unwrap();
+        let mldsa_public = PublicKey::from_bytes(
+            Algorithm::Mldsa65,
+            &public.as_bytes()[..Algorithm::Mldsa65.spec().public_key_len],
+        )
+        .unwrap();
+        assert!(!mldsa_public.verify(b"message", &component).unwrap());
+    }
+
+    #[test]
+    fn composite_private_key_is_seed_and_ed25519_key() {
+        let secret = generate(Algorithm::Mldsa44Ed25519);
+        assert_eq!(secret.default_format(), PrivateKeyFormat::Seed);
+        let private_key = secret.private_key(PrivateKeyFormat::Seed).unwrap();
+        assert_eq!(private_key.len(), composite::PRIVATE_KEY_LEN);
+        assert_eq!(&private_key[..32], secret.seed().unwrap());
+
+        let encoding = KeyEncoding {
+            format: PrivateKeyFormat::Seed,
+            with_public_key: true,
+        };
+        let decoded = SecretKey::from_der(&secret.to_der_as(encoding).unwrap()).unwrap();
+        assert!(decoded == secret);
+        assert!(matches!(
+            secret.to_der_as(PrivateKeyFormat::Expanded.into()),
+            Err(CryptoError::UnsupportedOption(_))
+        ));
+        assert!(matches!(
+            SecretKey::from_private_key(Algorithm::Mldsa44Ed25519, &private_key[1..], None),
+            Err(CryptoError::KeySizeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn context_binds_the_signature() {
+        let secret = SecretKey::from_seed(Algorithm::Mldsa44, &[7u8; 32]);
+        let public = secret.public_key();
+        let options = SignOptions {
+            context: b"transaction",
+            ..Default::default()
+        };
+        let signature = secret.sign_with(b"message", &options).unwrap();
+
+        assert!(public
+            .verify_with_context(b"message", &signature, b"transaction")
+            .unwrap());
+        assert!(!public
+            .verify_with_context(b"message", &signature, b"governance")
+            .unwrap());
+        assert!(!public.verify(b"message", &signature).unwrap());
+
+        let long = SignOptions {
+            context: &[0u8; 256],
+            ..Default::default()
+        };
+        assert!(matches!(
+            secret.sign_with(b"message", &long),
+            Err(CryptoError::InvalidContext(_))
+        ));
+        let legacy = SecretKey::from_seed(Algorithm::Dilithium2, &[7u8; 32]);
+        assert!(matches!(
+            legacy.sign_with(b"message", &options),
+            Err(CryptoError::InvalidContext(_))
+        ));
+    }
+
+    #[test]
+    fn deterministic_and_hedged_signing() {
+        let secret = SecretKey::from_seed(Algorithm::Mldsa44, &[7u8; 32]);
+        let hedged = SignOptions {
+            hedged: true,
+            ..Default::default()
+        };
+        assert!(secret.sign(b"message").unwrap() == secret.sign(b"message").unwrap());
+        let signature = secret.sign_with(b"message", &hedged).unwrap();
+        assert!(secret.public_key().verify(b"message", &signature).unwrap());
+
+        let legacy = SecretKey::from_seed(Algorithm::Dilithium2, &[7u8; 32]);
+        assert!(matches!(
+            legacy.sign_with(b"message", &hedged),
+            Err(CryptoError::UnsupportedOption(_))
+        ));
+    }
+
+    #[test]
+    fn malformed_input_is_rejected_without_panicking() {
+        assert!(matches!(
+            SecretKey::from_der(b"not a key"),
+            Err(CryptoError::DerDecode(_))
+        ));
+        assert!(matches!(
+            PublicKey::from_pem(b"not a key"),
+            Err(CryptoError::PemDecode(_))
+        ));
+
+        let public = generate(Algorithm::Mldsa65).public_key();
+        assert!(matches!(
+            PublicKey::from_bytes(Algorithm::Mldsa44, public.as_bytes()),
+            Err(CryptoError::KeySizeMismatch {
+                expected: 1312,
+                actual: 1952,
+                ..
+            })
+        ));
+        assert!(matches!(
+            Signature::from_bytes(Algorithm::Mldsa65, &[0u8; 10]),
+            Err(CryptoError::SignatureSizeMismatch { .. })
+        ));
+    }
+}
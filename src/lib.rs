@@ -0,0 +1,27 @@
+//! Dilithium and ML-DSA key generation, public key extraction, signing and verification.
+//!
+//! The `crypto` binary is a thin command line wrapper over this crate.
+mod algorithm;
+pub mod armor;
+pub mod asc1_dilithium;
+pub mod ca;
+pub mod chain;
+pub mod cms;
+mod composite;
+mod encrypted;
+mod envelope;
+mod error;
+mod fingerprint;
+mod keys;
+mod manifest;
+pub mod x509;
+
+pub use algorithm::{Algorithm, AlgorithmSpec, ALGORITHMS, MAX_CONTEXT_LEN};
+pub use encrypted::{Kdf, PBKDF2_ITERATIONS};
+pub use envelope::SignatureEnvelope;
+pub use error::CryptoError;
+pub use fingerprint::{Fingerprint, KEY_ID_LEN};
+pub use keys::{
+    generate, KeyEncoding, PrivateKeyFormat, PublicKey, SecretKey, SignOptions, Signature,
+};
+pub use manifest::{Manifest, ManifestDiff};
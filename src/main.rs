@@ -1,6 +1,24 @@
 use clap::Parser;
 mod commands;
-use commands::{GenerateCmd, PublicCmd, SignCmd, VerifyCmd};
+use commands::{
+    CaCmd, CertCmd, CmsSignCmd, CmsVerifyCmd, GenerateCmd, InspectCmd, PublicCmd, ReqCmd, SignCmd,
+    VerifyCmd,
+};
+use crypto::CryptoError;
+use std::process::ExitCode;
+
+/// Signature was checked and is not valid
+const EXIT_INVALID_SIGNATURE: u8 = 1;
+/// Command line usage error, as reported by clap
+const EXIT_USAGE: u8 = 2;
+/// Key, signature or argument value could not be decoded or is not supported
+const EXIT_MALFORMED_INPUT: u8 = 3;
+/// Reading or writing a file failed
+const EXIT_IO: u8 = 4;
+/// The operation itself failed, e.g. the signer rejected its input
+const EXIT_FAILURE: u8 = 5;
+/// A certificate the signature depends on is revoked by a CRL
+const EXIT_REVOKED: u8 = 6;
 
 #[derive(Debug, Parser)]
 #[clap(
@@ -14,25 +32,69 @@ pub enum Subkey {
     Public(PublicCmd),
     Sign(SignCmd),
     Verify(VerifyCmd),
+    Inspect(InspectCmd),
+    CmsSign(CmsSignCmd),
+    CmsVerify(CmsVerifyCmd),
+    Cert(CertCmd),
+    Req(ReqCmd),
+    Ca(CaCmd),
+}
+
+fn exit_code(err: &CryptoError) -> u8 {
+    match err {
+        CryptoError::File { source, .. } => exit_code(source),
+        CryptoError::VerificationFailed | CryptoError::PathValidation { .. } => {
+            EXIT_INVALID_SIGNATURE
+        }
+        CryptoError::Revoked { .. } => EXIT_REVOKED,
+        CryptoError::Io(_) => EXIT_IO,
+        CryptoError::SigningFailed(_) | CryptoError::CertificateAuthority(_) => EXIT_FAILURE,
+        CryptoError::InvalidArgument(_) => EXIT_USAGE,
+        CryptoError::RequestQrngError(_)
+        | CryptoError::InvalidAlgorithm(_)
+        | CryptoError::InvalidFormat(_)
+        | CryptoError::UnknownAlgorithmOid(_)
+        | CryptoError::DerDecode(_)
+        | CryptoError::PemDecode(_)
+        | CryptoError::KeySizeMismatch { .. }
+        | CryptoError::SignatureSizeMismatch { .. }
+        | CryptoError::MissingSeed
+        | CryptoError::MissingPublicKey
+        | CryptoError::KeyMismatch(_)
+        | CryptoError::Encryption(_)
+        | CryptoError::InvalidContext(_)
+        | CryptoError::UnsupportedOption(_)
+        | CryptoError::InvalidCms(_)
+        | CryptoError::InvalidCertificate(_)
+        | CryptoError::InvalidManifest(_)
+        | CryptoError::KeyNotFound(_)
+        | CryptoError::MissingPassword
+        | CryptoError::InvalidPassword(_)
+        | CryptoError::Der(_) => EXIT_MALFORMED_INPUT,
+    }
 }
 
-fn main() {
-    match Subkey::parse() {
-        Subkey::Generate(cmd) => match cmd.run() {
-            Ok(_) => {}
-            Err(err) => println!("ERROR: {:?}", err),
-        },
-        Subkey::Public(cmd) => match cmd.run() {
-            Ok(_) => {}
-            Err(err) => println!("ERROR: {:?}", err),
-        },
-        Subkey::Sign(cmd) => match cmd.run() {
-            Ok(_) => {}
-            Err(err) => println!("ERROR: {:?}", err),
-        },
-        Subkey::Verify(cmd) => match cmd.run() {
-            Ok(_) => {}
-            Err(err) => println!("ERROR: {:?}", err),
-        },
+fn main() -> ExitCode {
+    let result = match Subkey::parse() {
+        Subkey::Generate(cmd) => cmd.run(),
+        Subkey::Public(cmd) => cmd.run(),
+        Subkey::Sign(cmd) => cmd.run(),
+        Subkey::Verify(cmd) => cmd.run(),
+        Subkey::Inspect(cmd) => cmd.run(),
+        Subkey::CmsSign(cmd) => cmd.run(),
+        Subkey::CmsVerify(cmd) => cmd.run(),
+        Subkey::Cert(cmd) => cmd.run(),
+        Subkey::Req(cmd) => cmd.run(),
+        Subkey::Ca(cmd) => cmd.run(),
     };
+    match result {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(err) => {
+            // `verify` has already printed the outcome
+            if !matches!(err, CryptoError::VerificationFailed) {
+                eprintln!("ERROR: {}", err);
+            }
+            ExitCode::from(exit_code(&err))
+        }
+    }
 }
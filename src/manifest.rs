@@ -0,0 +1,174 @@
+//! Checksum manifests for directory trees, in the `sha256sum` format: one `<hex>  <path>` line
+//! per file, paths relative to the root with `/` separators, sorted by path. The canonical bytes
+//! are what `sign --dir` signs.
+use crate::error::CryptoError;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io;
+use std::path::Path;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Manifest {
+    /// SHA-256 of each file, by relative path
+    pub entries: BTreeMap<String, [u8; 32]>,
+}
+
+/// Files that differ between a manifest and the directory it is checked against
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ManifestDiff {
+    /// Listed in the manifest, absent from the directory
+    pub missing: Vec<String>,
+    /// Present in the directory, not listed in the manifest
+    pub extra: Vec<String>,
+    /// Present in both with different contents
+    pub modified: Vec<String>,
+}
+
+impl ManifestDiff {
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty() && self.modified.is_empty()
+    }
+}
+
+impl Manifest {
+    /// Hash every regular file below `root`. Symbolic links to files are hashed as their
+    /// target; symbolic links to directories are not followed.
+    pub fn from_dir(root: &Path) -> Result<Self, CryptoError> {
+        let mut manifest = Manifest::default();
+        manifest.add_dir(root, "")?;
+        Ok(manifest)
+    }
+
+    fn add_dir(&mut self, dir: &Path, prefix: &str) -> Result<(), CryptoError> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let name = entry.file_name().into_string().map_err(|name| {
+                CryptoError::InvalidManifest(format!("{:?} is not valid UTF-8", name))
+            })?;
+            let relative = format!("{}{}", prefix, name);
+            if relative.contains('\n') {
+                return Err(CryptoError::InvalidManifest(format!(
+                    "{:?} contains a newline",
+                    relative
+                )));
+            }
+            let path = entry.path();
+            if entry.file_type()?.is_dir() {
+                self.add_dir(&path, &format!("{}/", relative))?;
+            } else if path.is_file() {
+                let mut hasher = Sha256::new();
+                io::copy(&mut File::open(&path)?, &mut hasher)?;
+                self.entries.insert(relative, hasher.finalize().into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse `sha256sum` output; a `*` binary marker before the path is accepted
+    pub fn parse(bytes: &[u8]) -> Result<Self, CryptoError> {
+        let text = std::str::from_utf8(bytes)
+            .map_err(|_| CryptoError::InvalidManifest("not valid UTF-8".to_string()))?;
+        let mut manifest = Manifest::default();
+        for (number, line) in text.lines().enumerate() {
+            let invalid = || CryptoError::InvalidManifest(format!("line {}: {}", number + 1, line));
+            let (digest, path) = line.split_once(' ').ok_or_else(invalid)?;
+            let path = path
+                .strip_prefix(' ')
+                .or_else(|| path.strip_prefix('*'))
+                .filter(|path| !path.is_empty())
+                .ok_or_else(invalid)?;
+            let digest = decode_digest(digest).ok_or_else(invalid)?;
+            if manifest.entries.insert(path.to_string(), digest).is_some() {
+                return Err(CryptoError::InvalidManifest(format!(
+                    "{} is listed twice",
+                    path
+                )));
+            }
+        }
+        Ok(manifest)
+    }
+
+    /// Canonical encoding: sorted `<hex>  <path>` lines, each ending in `\n`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.entries
+            .iter()
+            .map(|(path, digest)| {
+                let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+                format!("{}  {}\n", hex, path)
+            })
+            .collect::<String>()
+            .into_bytes()
+    }
+
+    /// Compare this manifest with `actual`, the manifest of the directory as it is now
+    pub fn diff(&self, actual: &Manifest) -> ManifestDiff {
+        let mut diff = ManifestDiff::default();
+        for (path, digest) in &self.entries {
+            match actual.entries.get(path) {
+                None => diff.missing.push(path.clone()),
+                Some(actual) if actual != digest => diff.modified.push(path.clone()),
+                Some(_) => {}
+            }
+        }
+        diff.extra = actual
+            .entries
+            .keys()
+            .filter(|path| !self.entries.contains_key(*path))
+            .cloned()
+            .collect();
+        diff
+    }
+}
+
+fn decode_digest(hex: &str) -> Option<[u8; 32]> {
+    if hex.len() != 64 || !hex.is_ascii() {
+        return None;
+    }
+    let mut digest = [0u8; 32];
+    for (i, byte) in digest.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok()?;
+    }
+    Some(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_round_trip_and_diff() {
+        let root = Path::new("manifest_test_dir");
+        let _ = fs::remove_dir_all(root);
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join("b.txt"), b"b").unwrap();
+        fs::write(root.join("sub/a.txt"), b"a").unwrap();
+
+        let manifest = Manifest::from_dir(root).unwrap();
+        let text = String::from_utf8(manifest.to_bytes()).unwrap();
+        assert_eq!(
+            text,
+            "3e23e8160039594a33894f6564e1b1348bbd7a0088d42c4acb73eeaed59c009d  b.txt\n\
+             ca978112ca1bbdcafac231b39a23dc4da786eff8147c4e72b9807785afee48bb  sub/a.txt\n"
+        );
+        assert_eq!(Manifest::parse(text.as_bytes()).unwrap(), manifest);
+
+        fs::write(root.join("b.txt"), b"changed").unwrap();
+        fs::remove_file(root.join("sub/a.txt")).unwrap();
+        fs::write(root.join("c.txt"), b"c").unwrap();
+        let diff = manifest.diff(&Manifest::from_dir(root).unwrap());
+        assert_eq!(diff.missing, ["sub/a.txt"]);
+        assert_eq!(diff.extra, ["c.txt"]);
+        assert_eq!(diff.modified, ["b.txt"]);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn malformed_manifest_is_rejected() {
+        assert!(Manifest::parse(b"abcd  file\n").is_err());
+        let line = format!("{}  file\n", "00".repeat(32));
+        assert!(Manifest::parse(line.repeat(2).as_bytes()).is_err());
+        let binary = format!("{} *file\n", "00".repeat(32));
+        assert!(Manifest::parse(binary.as_bytes()).is_ok());
+    }
+}
@@ -0,0 +1,886 @@
+//! X.509 v3 certificates (RFC 5280) with ML-DSA and Dilithium keys. As in RFC 9881, the
+//! signature `AlgorithmIdentifier` is the key's OID with absent parameters, and the DER
+//! `tbsCertificate` is signed with pure ML-DSA and an empty context. CRLs are signed the same
+//! way over the DER `tbsCertList`.
+//!
+//! Subject and authority key identifiers are [`PublicKey::subject_key_identifier`], as in CMS.
+use crate::armor;
+use crate::error::CryptoError;
+use crate::keys::{PublicKey, SecretKey, Signature};
+use der::asn1::{
+    BitString, GeneralizedTime, Ia5String, ObjectIdentifier, OctetString, SetOfVec, Uint, UtcTime,
+};
+use der::flagset::FlagSet;
+use der::oid::AssociatedOid;
+use der::pem::PemLabel;
+use der::{Decode, Encode};
+use rand::{thread_rng, RngCore};
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use x509_cert::attr::Attribute;
+use x509_cert::crl::{RevokedCert, TbsCertList};
+use x509_cert::ext::pkix::crl::CrlNumber;
+use x509_cert::ext::pkix::name::GeneralName;
+use x509_cert::ext::pkix::{
+    AuthorityKeyIdentifier, BasicConstraints, ExtendedKeyUsage, KeyUsage, KeyUsages,
+    SubjectAltName, SubjectKeyIdentifier,
+};
+use x509_cert::ext::{AsExtension, Extension};
+use x509_cert::request::{CertReqInfo, ExtensionReq, Version as RequestVersion};
+use x509_cert::serial_number::SerialNumber;
+use x509_cert::spki::{AlgorithmIdentifierOwned, SubjectPublicKeyInfoOwned};
+use x509_cert::time::{Time, Validity};
+use x509_cert::{TbsCertificate, Version};
+
+pub use x509_cert::crl::CertificateList;
+pub use x509_cert::ext::pkix::crl::CrlReason;
+pub use x509_cert::name::Name;
+pub use x509_cert::request::CertReq;
+pub use x509_cert::Certificate;
+
+/// PEM label of a CRL, as in RFC 7468
+pub const CRL_PEM_LABEL: &str = "X509 CRL";
+
+/// Key usages a signature key can carry
+const SIGNING_USAGES: [(&str, KeyUsages); 4] = [
+    ("digitalSignature", KeyUsages::DigitalSignature),
+    ("nonRepudiation", KeyUsages::NonRepudiation),
+    ("keyCertSign", KeyUsages::KeyCertSign),
+    ("cRLSign", KeyUsages::CRLSign),
+];
+
+/// Key purposes of the issuing profiles (RFC 5280 section 4.2.1.12)
+pub const ID_KP_SERVER_AUTH: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.5.5.7.3.1");
+pub const ID_KP_CLIENT_AUTH: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.5.5.7.3.2");
+pub const ID_KP_CODE_SIGNING: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.5.5.7.3.3");
+pub const ANY_EXTENDED_KEY_USAGE: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.5.29.37.0");
+
+const KEY_PURPOSES: [(&str, ObjectIdentifier); 4] = [
+    ("serverAuth", ID_KP_SERVER_AUTH),
+    ("clientAuth", ID_KP_CLIENT_AUTH),
+    ("codeSigning", ID_KP_CODE_SIGNING),
+    ("anyExtendedKeyUsage", ANY_EXTENDED_KEY_USAGE),
+];
+
+/// Extensions a certificate carries or a request asks for, besides the key identifiers
+#[derive(Clone, Debug, Default)]
+pub struct ExtensionOptions {
+    /// basicConstraints `cA`; `path_len` is only written for a CA
+    pub ca: bool,
+    pub path_len: Option<u8>,
+    /// digitalSignature, plus keyCertSign and cRLSign for a CA, when `None`
+    pub key_usage: Option<FlagSet<KeyUsages>>,
+    pub subject_alt_names: Vec<GeneralName>,
+    /// extKeyUsage key purposes, left out when empty
+    pub extended_key_usage: Vec<ObjectIdentifier>,
+}
+
+impl ExtensionOptions {
+    /// basicConstraints, keyUsage and, when set, extKeyUsage and subjectAltName
+    fn to_extensions(&self, subject: &Name) -> Result<Vec<Extension>, CryptoError> {
+        let key_usage = self.key_usage.unwrap_or_else(|| {
+            if self.ca {
+                KeyUsages::DigitalSignature | KeyUsages::KeyCertSign | KeyUsages::CRLSign
+            } else {
+                KeyUsages::DigitalSignature.into()
+            }
+        });
+        let mut extensions = Vec::new();
+        extensions.push(
+            BasicConstraints {
+                ca: self.ca,
+                path_len_constraint: self.path_len.filter(|_| self.ca),
+            }
+            .to_extension(subject, &extensions)?,
+        );
+        extensions.push(KeyUsage(key_usage).to_extension(subject, &extensions)?);
+        if !self.extended_key_usage.is_empty() {
+            // Not critical, as the CA/Browser Forum requires of TLS certificates
+            extensions.push(Extension {
+                extn_id: ExtendedKeyUsage::OID,
+                critical: false,
+                extn_value: OctetString::new(
+                    ExtendedKeyUsage(self.extended_key_usage.clone()).to_der()?,
+                )?,
+            });
+        }
+        if !self.subject_alt_names.is_empty() {
+            extensions.push(
+                SubjectAltName(self.subject_alt_names.clone())
+                    .to_extension(subject, &extensions)?,
+            );
+        }
+        Ok(extensions)
+    }
+}
+
+/// Contents of a certificate other than its keys
+#[derive(Clone, Debug)]
+pub struct CertificateOptions {
+    pub subject: Name,
+    pub not_before: SystemTime,
+    pub not_after: SystemTime,
+    /// Random 16-byte serial number when `None`
+    pub serial_number: Option<Vec<u8>>,
+    pub extensions: ExtensionOptions,
+}
+
+impl CertificateOptions {
+    /// End-entity certificate for `subject`, valid from now for `days`
+    pub fn new(subject: Name, days: u32) -> Self {
+        let now = SystemTime::now();
+        CertificateOptions {
+            subject,
+            not_before: now,
+            not_after: now + Duration::from_secs(u64::from(days) * 24 * 60 * 60),
+            serial_number: None,
+            extensions: ExtensionOptions::default(),
+        }
+    }
+}
+
+/// Self-signed certificate for the public key of `secret_key`
+pub fn self_signed(
+    secret_key: &SecretKey,
+    options: &CertificateOptions,
+) -> Result<Certificate, CryptoError> {
+    let public_key = secret_key.public_key();
+    let key_identifier = public_key.subject_key_identifier();
+    build(
+        secret_key,
+        &options.subject,
+        &key_identifier,
+        &public_key,
+        options,
+    )
+}
+
+/// Certificate for `subject_key` signed by `issuer_key`, the key of the CA certificate `issuer`.
+/// The issuer must be a CA allowed to sign certificates, and one whose pathLen constraint
+/// leaves room when `options` asks for another CA.
+pub fn issue(
+    issuer_key: &SecretKey,
+    issuer: &Certificate,
+    subject_key: &PublicKey,
+    options: &CertificateOptions,
+) -> Result<Certificate, CryptoError> {
+    check_issuer_key(issuer_key, issuer)?;
+    let basic = extension(issuer, &BasicConstraints::OID)
+        .map(|basic| BasicConstraints::from_der(basic.extn_value.as_bytes()))
+        .transpose()?;
+    match basic {
+        Some(basic) if basic.ca => {
+            if options.extensions.ca && basic.path_len_constraint == Some(0) {
+                return Err(CryptoError::InvalidCertificate(
+                    "the issuer's pathLen constraint allows no further CA".to_string(),
+                ));
+            }
+        }
+        _ => {
+            return Err(CryptoError::InvalidCertificate(
+                "the issuer certificate is not a CA".to_string(),
+            ))
+        }
+    }
+    if let Some(key_usage) = extension(issuer, &KeyUsage::OID) {
+        if !KeyUsage::from_der(key_usage.extn_value.as_bytes())?.key_cert_sign() {
+            return Err(CryptoError::InvalidCertificate(
+                "the issuer's keyUsage does not include keyCertSign".to_string(),
+            ));
+        }
+    }
+    build(
+        issuer_key,
+        &issuer.tbs_certificate.subject,
+        &key_identifier(issuer)?,
+        subject_key,
+        options,
+    )
+}
+
+fn check_issuer_key(issuer_key: &SecretKey, issuer: &Certificate) -> Result<(), CryptoError> {
+    if public_key(issuer)? != issuer_key.public_key() {
+        return Err(CryptoError::KeyMismatch(
+            "the private key does not belong to the issuer certificate".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// subjectKeyIdentifier of `certificate`, or the one computed from its key when it has none
+pub fn key_identifier(certificate: &Certificate) -> Result<Vec<u8>, CryptoError> {
+    Ok(match extension(certificate, &SubjectKeyIdentifier::OID) {
+        Some(ski) => SubjectKeyIdentifier::from_der(ski.extn_value.as_bytes())?
+            .0
+            .as_bytes()
+            .to_vec(),
+        None => public_key(certificate)?.subject_key_identifier().to_vec(),
+    })
+}
+
+/// Certificate for `subject_key` issued by `issuer`, whose key identifier is
+/// `authority_key_identifier`
+fn build(
+    issuer_key: &SecretKey,
+    issuer: &Name,
+    authority_key_identifier: &[u8],
+    subject_key: &PublicKey,
+    options: &CertificateOptions,
+) -> Result<Certificate, CryptoError> {
+    if options.subject.0.is_empty() {
+        return Err(CryptoError::InvalidCertificate(
+            "a certificate needs a subject name".to_string(),
+        ));
+    }
+    let subject = &options.subject;
+    let mut extensions = options.extensions.to_extensions(subject)?;
+    extensions.push(
+        SubjectKeyIdentifier(OctetString::new(subject_key.subject_key_identifier())?)
+            .to_extension(subject, &extensions)?,
+    );
+    extensions.push(
+        AuthorityKeyIdentifier {
+            key_identifier: Some(OctetString::new(authority_key_identifier)?),
+            authority_cert_issuer: None,
+            authority_cert_serial_number: None,
+        }
+        .to_extension(subject, &extensions)?,
+    );
+
+    let serial_number = match &options.serial_number {
+        Some(serial_number) => serial_number.clone(),
+        None => random_serial_number(),
+    };
+    let tbs_certificate = TbsCertificate {
+        version: Version::V3,
+        serial_number: SerialNumber::new(&serial_number)?,
+        signature: signature_algorithm(&issuer_key.public_key()),
+        issuer: issuer.clone(),
+        validity: Validity {
+            not_before: time(options.not_before)?,
+            not_after: time(options.not_after)?,
+        },
+        subject: subject.clone(),
+        subject_public_key_info: SubjectPublicKeyInfoOwned::from_der(&subject_key.to_der()?)?,
+        issuer_unique_id: None,
+        subject_unique_id: None,
+        extensions: Some(extensions),
+    };
+    sign(issuer_key, tbs_certificate)
+}
+
+/// Sign `tbs_certificate` with `secret_key`, whose algorithm it must name
+fn sign(
+    secret_key: &SecretKey,
+    tbs_certificate: TbsCertificate,
+) -> Result<Certificate, CryptoError> {
+    let signature = secret_key.sign(&tbs_certificate.to_der()?)?;
+    Ok(Certificate {
+        signature_algorithm: tbs_certificate.signature.clone(),
+        tbs_certificate,
+        signature: BitString::from_bytes(signature.as_bytes())?,
+    })
+}
+
+/// Check the signature on `certificate` with the issuer's public key. `Ok(false)` means the
+/// signature is invalid.
+pub fn verify_signature(
+    certificate: &Certificate,
+    issuer_key: &PublicKey,
+) -> Result<bool, CryptoError> {
+    if certificate.tbs_certificate.signature != certificate.signature_algorithm {
+        return Err(CryptoError::InvalidCertificate(
+            "signatureAlgorithm does not match the signature field of tbsCertificate".to_string(),
+        ));
+    }
+    verify_signed(
+        &certificate.tbs_certificate.to_der()?,
+        &certificate.signature_algorithm,
+        &certificate.signature,
+        issuer_key,
+    )
+}
+
+/// Check `signature` over the DER `tbs` with `issuer_key`, which `algorithm` must name
+fn verify_signed(
+    tbs: &[u8],
+    algorithm: &AlgorithmIdentifierOwned,
+    signature: &BitString,
+    issuer_key: &PublicKey,
+) -> Result<bool, CryptoError> {
+    if algorithm.oid != issuer_key.algorithm().oid() || algorithm.parameters.is_some() {
+        return Err(CryptoError::InvalidAlgorithm(algorithm.oid.to_string()));
+    }
+    let signature = signature.as_bytes().ok_or_else(|| {
+        CryptoError::InvalidCertificate("the signature has unused bits".to_string())
+    })?;
+    let signature = Signature::from_bytes(issuer_key.algorithm(), signature)?;
+    issuer_key.verify(tbs, &signature)
+}
+
+/// Subject public key of `certificate`
+pub fn public_key(certificate: &Certificate) -> Result<PublicKey, CryptoError> {
+    PublicKey::from_der(
+        &certificate
+            .tbs_certificate
+            .subject_public_key_info
+            .to_der()?,
+    )
+}
+
+/// Certificate from PEM (`CERTIFICATE`) or DER
+pub fn decode(bytes: &[u8]) -> Result<Certificate, CryptoError> {
+    Certificate::from_der(&pem_or_der(bytes, Certificate::PEM_LABEL)?)
+        .map_err(CryptoError::DerDecode)
+}
+
+/// Certificates of a PEM bundle, or the single certificate of a DER file. Text around the PEM
+/// blocks, such as the `subject=` lines OpenSSL writes, is skipped.
+pub fn decode_bundle(bytes: &[u8]) -> Result<Vec<Certificate>, CryptoError> {
+    if bytes.first() == Some(&0x30) {
+        return Ok(vec![decode(bytes)?]);
+    }
+    let mut certificates = Vec::new();
+    let mut rest = bytes;
+    while let Some(start) = rest.windows(11).position(|window| window == b"-----BEGIN ") {
+        let block = &rest[start..];
+        certificates.push(decode(block)?);
+        // Skip past this block's END line
+        let end = block
+            .windows(9)
+            .position(|window| window == b"-----END ")
+            .map_or(block.len(), |end| end + 9);
+        rest = &block[end..];
+    }
+    if certificates.is_empty() {
+        return Err(CryptoError::PemDecode(
+            der::pem::Error::PreEncapsulationBoundary.into(),
+        ));
+    }
+    Ok(certificates)
+}
+
+pub fn to_pem(certificate: &Certificate) -> Result<String, CryptoError> {
+    Ok(armor::encode(
+        Certificate::PEM_LABEL,
+        &certificate.to_der()?,
+    ))
+}
+
+/// PKCS#10 certification request (RFC 2986) for the public key of `secret_key`, asking for
+/// `extensions` in an extensionRequest attribute. The signature proves possession of the key.
+pub fn request(
+    secret_key: &SecretKey,
+    subject: &Name,
+    extensions: &ExtensionOptions,
+) -> Result<CertReq, CryptoError> {
+    let public_key = secret_key.public_key();
+    let extension_req = ExtensionReq(extensions.to_extensions(subject)?);
+    let info = CertReqInfo {
+        version: RequestVersion::V1,
+        subject: subject.clone(),
+        public_key: SubjectPublicKeyInfoOwned::from_der(&public_key.to_der()?)?,
+        attributes: SetOfVec::try_from(vec![Attribute::try_from(extension_req)?])?,
+    };
+    let signature = secret_key.sign(&info.to_der()?)?;
+    Ok(CertReq {
+        info,
+        algorithm: signature_algorithm(&public_key),
+        signature: BitString::from_bytes(signature.as_bytes())?,
+    })
+}
+
+/// Check the proof of possession: the signature on `request` by the key it carries.
+/// `Ok(false)` means the signature is invalid.
+pub fn verify_request(request: &CertReq) -> Result<bool, CryptoError> {
+    let public_key = request_public_key(request)?;
+    let algorithm = &request.algorithm;
+    if algorithm.oid != public_key.algorithm().oid() || algorithm.parameters.is_some() {
+        return Err(CryptoError::InvalidAlgorithm(algorithm.oid.to_string()));
+    }
+    let signature = request.signature.as_bytes().ok_or_else(|| {
+        CryptoError::InvalidCertificate("the signature has unused bits".to_string())
+    })?;
+    let signature = Signature::from_bytes(public_key.algorithm(), signature)?;
+    public_key.verify(&request.info.to_der()?, &signature)
+}
+
+pub fn request_public_key(request: &CertReq) -> Result<PublicKey, CryptoError> {
+    PublicKey::from_der(&request.info.public_key.to_der()?)
+}
+
+/// Extensions of the extensionRequest attribute, empty when there is none
+pub fn requested_extensions(request: &CertReq) -> Result<Vec<Extension>, CryptoError> {
+    let mut extensions = Vec::new();
+    for attribute in request.info.attributes.iter() {
+        if attribute.oid != ExtensionReq::OID {
+            continue;
+        }
+        for value in attribute.values.iter() {
+            let ExtensionReq(requested) = value.decode_as().map_err(CryptoError::DerDecode)?;
+            extensions.extend(requested);
+        }
+    }
+    Ok(extensions)
+}
+
+/// Certification request from PEM (`CERTIFICATE REQUEST`) or DER
+pub fn decode_request(bytes: &[u8]) -> Result<CertReq, CryptoError> {
+    CertReq::from_der(&pem_or_der(bytes, CertReq::PEM_LABEL)?).map_err(CryptoError::DerDecode)
+}
+
+pub fn request_to_pem(request: &CertReq) -> Result<String, CryptoError> {
+    Ok(armor::encode(CertReq::PEM_LABEL, &request.to_der()?))
+}
+
+/// Revoked certificate listed in a CRL
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RevokedCertificate {
+    /// Serial number as DER INTEGER content bytes
+    pub serial_number: Vec<u8>,
+    pub time: SystemTime,
+    pub reason: CrlReason,
+}
+
+/// Contents of a CRL
+#[derive(Clone, Debug)]
+pub struct CrlOptions {
+    pub this_update: SystemTime,
+    pub next_update: SystemTime,
+    /// cRLNumber as DER INTEGER content bytes, increasing with each CRL of the issuer
+    pub number: Vec<u8>,
+    pub revoked: Vec<RevokedCertificate>,
+}
+
+/// X.509 v2 CRL (RFC 5280 section 5) signed by `issuer_key`, the key of `issuer`, with
+/// authorityKeyIdentifier and cRLNumber extensions. Entries revoked for `unspecified` carry
+/// no reasonCode, as RFC 5280 recommends.
+pub fn crl(
+    issuer_key: &SecretKey,
+    issuer: &Certificate,
+    options: &CrlOptions,
+) -> Result<CertificateList, CryptoError> {
+    check_issuer_key(issuer_key, issuer)?;
+    if let Some(key_usage) = extension(issuer, &KeyUsage::OID) {
+        if !KeyUsage::from_der(key_usage.extn_value.as_bytes())?.crl_sign() {
+            return Err(CryptoError::InvalidCertificate(
+                "the issuer's keyUsage does not include cRLSign".to_string(),
+            ));
+        }
+    }
+    let name = &issuer.tbs_certificate.subject;
+    let mut revoked_certificates = Vec::new();
+    for revoked in &options.revoked {
+        let crl_entry_extensions = match revoked.reason {
+            CrlReason::Unspecified => None,
+            reason => Some(vec![reason.to_extension(name, &[])?]),
+        };
+        revoked_certificates.push(RevokedCert {
+            serial_number: SerialNumber::new(&revoked.serial_number)?,
+            revocation_date: time(revoked.time)?,
+            crl_entry_extensions,
+        });
+    }
+    let mut extensions = vec![AuthorityKeyIdentifier {
+        key_identifier: Some(OctetString::new(key_identifier(issuer)?)?),
+        authority_cert_issuer: None,
+        authority_cert_serial_number: None,
+    }
+    .to_extension(name, &[])?];
+    extensions.push(CrlNumber(Uint::new(&options.number)?).to_extension(name, &extensions)?);
+
+    let tbs_cert_list = TbsCertList {
+        version: Version::V2,
+        signature: signature_algorithm(&issuer_key.public_key()),
+        issuer: name.clone(),
+        this_update: time(options.this_update)?,
+        next_update: Some(time(options.next_update)?),
+        revoked_certificates: (!revoked_certificates.is_empty()).then_some(revoked_certificates),
+        crl_extensions: Some(extensions),
+    };
+    let signature = issuer_key.sign(&tbs_cert_list.to_der()?)?;
+    Ok(CertificateList {
+        signature_algorithm: tbs_cert_list.signature.clone(),
+        tbs_cert_list,
+        signature: BitString::from_bytes(signature.as_bytes())?,
+    })
+}
+
+/// Check the signature on `crl` with the issuer's public key. `Ok(false)` means the signature
+/// is invalid.
+pub fn verify_crl_signature(
+    crl: &CertificateList,
+    issuer_key: &PublicKey,
+) -> Result<bool, CryptoError> {
+    if crl.tbs_cert_list.signature != crl.signature_algorithm {
+        return Err(CryptoError::InvalidCertificate(
+            "signatureAlgorithm does not match the signature field of tbsCertList".to_string(),
+        ));
+    }
+    verify_signed(
+        &crl.tbs_cert_list.to_der()?,
+        &crl.signature_algorithm,
+        &crl.signature,
+        issuer_key,
+    )
+}
+
+/// Entries of `crl`; a missing reasonCode is `unspecified`
+pub fn revoked_certificates(crl: &CertificateList) -> Result<Vec<RevokedCertificate>, CryptoError> {
+    let mut revoked = Vec::new();
+    for entry in crl.tbs_cert_list.revoked_certificates.iter().flatten() {
+        let reason = entry
+            .crl_entry_extensions
+            .iter()
+            .flatten()
+            .find(|extension| extension.extn_id == CrlReason::OID)
+            .map(|extension| CrlReason::from_der(extension.extn_value.as_bytes()))
+            .transpose()?
+            .unwrap_or(CrlReason::Unspecified);
+        revoked.push(RevokedCertificate {
+            serial_number: entry.serial_number.as_bytes().to_vec(),
+            time: entry.revocation_date.to_system_time(),
+            reason,
+        });
+    }
+    Ok(revoked)
+}
+
+/// RFC 5280 name of a revocation reason, e.g. `keyCompromise`
+pub fn reason_name(reason: CrlReason) -> &'static str {
+    match reason {
+        CrlReason::Unspecified => "unspecified",
+        CrlReason::KeyCompromise => "keyCompromise",
+        CrlReason::CaCompromise => "CACompromise",
+        CrlReason::AffiliationChanged => "affiliationChanged",
+        CrlReason::Superseded => "superseded",
+        CrlReason::CessationOfOperation => "cessationOfOperation",
+        CrlReason::CertificateHold => "certificateHold",
+        CrlReason::RemoveFromCRL => "removeFromCRL",
+        CrlReason::PrivilegeWithdrawn => "privilegeWithdrawn",
+        CrlReason::AaCompromise => "aACompromise",
+    }
+}
+
+/// CRL from PEM (`X509 CRL`) or DER
+pub fn decode_crl(bytes: &[u8]) -> Result<CertificateList, CryptoError> {
+    CertificateList::from_der(&pem_or_der(bytes, CRL_PEM_LABEL)?).map_err(CryptoError::DerDecode)
+}
+
+pub fn crl_to_pem(crl: &CertificateList) -> Result<String, CryptoError> {
+    Ok(armor::encode(CRL_PEM_LABEL, &crl.to_der()?))
+}
+
+/// DER of `bytes`, unwrapping PEM with the given label
+fn pem_or_der(bytes: &[u8], label: &str) -> Result<Vec<u8>, CryptoError> {
+    if !bytes.trim_ascii_start().starts_with(b"-----BEGIN ") {
+        return Ok(bytes.to_vec());
+    }
+    let (found, der) = armor::decode(bytes)?;
+    if found != label {
+        return Err(CryptoError::PemDecode(der::pem::Error::Label.into()));
+    }
+    Ok(der)
+}
+
+/// One line description of an extension, e.g. `keyUsage (critical): digitalSignature`.
+/// Extensions this module does not write are shown as their OID and hex value.
+pub fn describe_extension(extension: &Extension) -> String {
+    let value = extension.extn_value.as_bytes();
+    let oid = extension.extn_id;
+    let described = if oid == BasicConstraints::OID {
+        BasicConstraints::from_der(value).ok().map(|basic| {
+            let path_len = basic
+                .path_len_constraint
+                .map(|path_len| format!(", pathLen={}", path_len))
+                .unwrap_or_default();
+            ("basicConstraints", format!("cA={}{}", basic.ca, path_len))
+        })
+    } else if oid == KeyUsage::OID {
+        KeyUsage::from_der(value).ok().map(|usage| {
+            let names: Vec<&str> = SIGNING_USAGES
+                .iter()
+                .filter(|(_, flag)| usage.0.contains(*flag))
+                .map(|(name, _)| *name)
+                .collect();
+            ("keyUsage", names.join(", "))
+        })
+    } else if oid == ExtendedKeyUsage::OID {
+        ExtendedKeyUsage::from_der(value).ok().map(|usage| {
+            let names: Vec<String> = usage
+                .0
+                .iter()
+                .map(
+                    |purpose| match KEY_PURPOSES.iter().find(|(_, oid)| oid == purpose) {
+                        Some((name, _)) => name.to_string(),
+                        None => purpose.to_string(),
+                    },
+                )
+                .collect();
+            ("extKeyUsage", names.join(", "))
+        })
+    } else if oid == SubjectAltName::OID {
+        SubjectAltName::from_der(value).ok().map(|names| {
+            let names: Vec<String> = names.0.iter().map(general_name_to_string).collect();
+            ("subjectAltName", names.join(", "))
+        })
+    } else if oid == SubjectKeyIdentifier::OID {
+        SubjectKeyIdentifier::from_der(value)
+            .ok()
+            .map(|ski| ("subjectKeyIdentifier", hex(ski.0.as_bytes())))
+    } else if oid == AuthorityKeyIdentifier::OID {
+        AuthorityKeyIdentifier::from_der(value).ok().map(|aki| {
+            let key_identifier = aki.key_identifier.map(|id| hex(id.as_bytes()));
+            ("authorityKeyIdentifier", key_identifier.unwrap_or_default())
+        })
+    } else {
+        None
+    };
+    let (name, text) = match described {
+        Some((name, text)) => (name.to_string(), text),
+        None => (oid.to_string(), hex(value)),
+    };
+    let critical = if extension.critical {
+        " (critical)"
+    } else {
+        ""
+    };
+    format!("{}{}: {}", name, critical, text)
+}
+
+/// subjectAltName entry in the form [`parse_general_name`] accepts
+fn general_name_to_string(name: &GeneralName) -> String {
+    match name {
+        GeneralName::DnsName(name) => format!("DNS:{}", name),
+        GeneralName::Rfc822Name(name) => format!("email:{}", name),
+        GeneralName::UniformResourceIdentifier(name) => format!("URI:{}", name),
+        GeneralName::IpAddress(address) => {
+            let bytes = address.as_bytes();
+            match (<[u8; 4]>::try_from(bytes), <[u8; 16]>::try_from(bytes)) {
+                (Ok(v4), _) => format!("IP:{}", IpAddr::from(v4)),
+                (_, Ok(v6)) => format!("IP:{}", IpAddr::from(v6)),
+                _ => format!("IP:{}", hex(bytes)),
+            }
+        }
+        GeneralName::DirectoryName(name) => format!("dirName:{}", name),
+        _ => "other name".to_string(),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Distinguished name in RFC 4514 form, e.g. `CN=service,O=Example`
+pub fn parse_name(name: &str) -> Result<Name, CryptoError> {
+    Name::from_str(name)
+        .map_err(|err| CryptoError::InvalidArgument(format!("name {:?}: {}", name, err)))
+}
+
+/// subjectAltName entry: `DNS:host`, `IP:address`, `email:address` or `URI:uri`
+pub fn parse_general_name(name: &str) -> Result<GeneralName, CryptoError> {
+    let invalid = || {
+        CryptoError::InvalidArgument(format!(
+            "subject alternative name {:?}, expected DNS:, IP:, email: or URI:",
+            name
+        ))
+    };
+    let (kind, value) = name.split_once(':').ok_or_else(invalid)?;
+    let ia5 = || Ia5String::new(value).map_err(|_| invalid());
+    Ok(match kind.to_ascii_lowercase().as_str() {
+        "dns" => GeneralName::DnsName(ia5()?),
+        "email" => GeneralName::Rfc822Name(ia5()?),
+        "uri" => GeneralName::UniformResourceIdentifier(ia5()?),
+        "ip" => {
+            let address = IpAddr::from_str(value).map_err(|_| invalid())?;
+            let bytes = match address {
+                IpAddr::V4(address) => address.octets().to_vec(),
+                IpAddr::V6(address) => address.octets().to_vec(),
+            };
+            GeneralName::IpAddress(OctetString::new(bytes)?)
+        }
+        _ => return Err(invalid()),
+    })
+}
+
+/// Comma separated keyUsage names as in RFC 5280, e.g. `digitalSignature,keyCertSign`.
+/// Only the usages of a signature key are accepted.
+pub fn parse_key_usage(usages: &str) -> Result<FlagSet<KeyUsages>, CryptoError> {
+    let mut flags = FlagSet::default();
+    for usage in usages.split(',').map(str::trim) {
+        let (_, flag) = SIGNING_USAGES
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(usage))
+            .ok_or_else(|| {
+                CryptoError::InvalidArgument(format!(
+                    "key usage {:?}, expected one of {}",
+                    usage,
+                    SIGNING_USAGES.map(|(name, _)| name).join(", ")
+                ))
+            })?;
+        flags |= *flag;
+    }
+    Ok(flags)
+}
+
+/// Time in the RFC 3339 form `YYYY-MM-DDTHH:MM:SSZ`
+pub fn parse_time(time: &str) -> Result<SystemTime, CryptoError> {
+    der::DateTime::from_str(time)
+        .map(|time| time.to_system_time())
+        .map_err(|_| {
+            CryptoError::InvalidArgument(format!("time {:?}, expected YYYY-MM-DDTHH:MM:SSZ", time))
+        })
+}
+
+/// UTCTime through 2049 and GeneralizedTime after, in whole seconds, as RFC 5280 requires
+fn time(time: SystemTime) -> Result<Time, CryptoError> {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| CryptoError::InvalidArgument("time before 1970".to_string()))?
+        .as_secs();
+    let time = UNIX_EPOCH + Duration::from_secs(seconds);
+    Ok(match UtcTime::from_system_time(time) {
+        Ok(time) => Time::UtcTime(time),
+        Err(_) => Time::GeneralTime(GeneralizedTime::from_system_time(time)?),
+    })
+}
+
+/// Positive and at most 20 bytes, as RFC 5280 requires
+pub(crate) fn random_serial_number() -> Vec<u8> {
+    let mut serial_number = vec![0u8; 16];
+    thread_rng().fill_bytes(&mut serial_number);
+    serial_number[0] = serial_number[0] & 0x7f | 0x01;
+    serial_number
+}
+
+fn signature_algorithm(public_key: &PublicKey) -> AlgorithmIdentifierOwned {
+    AlgorithmIdentifierOwned {
+        oid: public_key.algorithm().oid(),
+        parameters: None,
+    }
+}
+
+/// Extension `oid` of `certificate`, if present
+pub fn extension<'a>(
+    certificate: &'a Certificate,
+    oid: &der::asn1::ObjectIdentifier,
+) -> Option<&'a Extension> {
+    certificate
+        .tbs_certificate
+        .extensions
+        .as_ref()?
+        .iter()
+        .find(|extension| extension.extn_id == *oid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::Algorithm;
+
+    #[test]
+    fn self_signed_round_trip() {
+        let secret = SecretKey::from_seed(Algorithm::Mldsa65, &[7u8; 32]);
+        let mut options = CertificateOptions::new(parse_name("CN=service,O=Example").unwrap(), 30);
+        options.extensions.subject_alt_names = vec![
+            parse_general_name("DNS:service.example").unwrap(),
+            parse_general_name("IP:10.0.0.1").unwrap(),
+        ];
+        let certificate = self_signed(&secret, &options).unwrap();
+
+        let decoded = decode(to_pem(&certificate).unwrap().as_bytes()).unwrap();
+        assert_eq!(decoded, certificate);
+        assert_eq!(decode(&certificate.to_der().unwrap()).unwrap(), certificate);
+        let pem = to_pem(&certificate).unwrap();
+        let bundle = format!("subject=CN=service\n{}\n{}", pem, pem);
+        assert_eq!(decode_bundle(bundle.as_bytes()).unwrap().len(), 2);
+        let der_bundle = decode_bundle(&certificate.to_der().unwrap()).unwrap();
+        assert!(der_bundle.len() == 1 && der_bundle[0] == certificate);
+        let tbs = &certificate.tbs_certificate;
+        assert_eq!(tbs.subject.to_string(), "CN=service,O=Example");
+        assert_eq!(tbs.issuer, tbs.subject);
+        assert!(matches!(tbs.validity.not_before, Time::UtcTime(_)));
+        assert_eq!(public_key(&certificate).unwrap(), secret.public_key());
+        assert!(verify_signature(&certificate, &secret.public_key()).unwrap());
+
+        let ski = extension(&certificate, &SubjectKeyIdentifier::OID).unwrap();
+        let ski = SubjectKeyIdentifier::from_der(ski.extn_value.as_bytes()).unwrap();
+        assert_eq!(
+            ski.0.as_bytes(),
+            secret.public_key().subject_key_identifier()
+        );
+        let key_usage = extension(&certificate, &KeyUsage::OID).unwrap();
+        assert!(key_usage.critical);
+
+        let other = SecretKey::from_seed(Algorithm::Mldsa65, &[8u8; 32]).public_key();
+        assert!(!verify_signature(&certificate, &other).unwrap());
+    }
+
+    #[test]
+    fn ca_certificate_extensions() {
+        let secret = SecretKey::from_seed(Algorithm::Mldsa87, &[7u8; 32]);
+        let mut options = CertificateOptions::new(parse_name("CN=Root").unwrap(), 3650);
+        options.extensions.ca = true;
+        options.extensions.path_len = Some(1);
+        options.not_after = parse_time("2060-01-01T00:00:00Z").unwrap();
+        let certificate = self_signed(&secret, &options).unwrap();
+
+        let basic = extension(&certificate, &BasicConstraints::OID).unwrap();
+        let basic = BasicConstraints::from_der(basic.extn_value.as_bytes()).unwrap();
+        assert!(basic.ca);
+        assert_eq!(basic.path_len_constraint, Some(1));
+        let key_usage = extension(&certificate, &KeyUsage::OID).unwrap();
+        let key_usage = KeyUsage::from_der(key_usage.extn_value.as_bytes()).unwrap();
+        assert!(key_usage.key_cert_sign() && key_usage.crl_sign());
+        assert!(matches!(
+            certificate.tbs_certificate.validity.not_after,
+            Time::GeneralTime(_)
+        ));
+    }
+
+    #[test]
+    fn request_proves_possession() {
+        let secret = SecretKey::from_seed(Algorithm::Mldsa44, &[7u8; 32]);
+        let extensions = ExtensionOptions {
+            subject_alt_names: vec![parse_general_name("IP:::1").unwrap()],
+            ..Default::default()
+        };
+        let request = request(&secret, &parse_name("CN=client").unwrap(), &extensions).unwrap();
+        let decoded = decode_request(request_to_pem(&request).unwrap().as_bytes()).unwrap();
+        assert_eq!(decoded, request);
+        assert!(verify_request(&request).unwrap());
+        assert_eq!(request_public_key(&request).unwrap(), secret.public_key());
+
+        let described: Vec<String> = requested_extensions(&request)
+            .unwrap()
+            .iter()
+            .map(describe_extension)
+            .collect();
+        assert_eq!(
+            described,
+            [
+                "basicConstraints (critical): cA=false",
+                "keyUsage (critical): digitalSignature",
+                "subjectAltName: IP:::1",
+            ]
+        );
+
+        let mut tampered = request.clone();
+        tampered.info.subject = parse_name("CN=someone else").unwrap();
+        assert!(!verify_request(&tampered).unwrap());
+    }
+
+    #[test]
+    fn invalid_arguments_are_rejected() {
+        assert!(parse_name("not a name").is_err());
+        assert!(parse_general_name("host.example").is_err());
+        assert!(parse_general_name("IP:300.0.0.1").is_err());
+        assert!(parse_key_usage("keyEncipherment").is_err());
+        assert_eq!(
+            parse_key_usage("digitalSignature, cRLSign").unwrap(),
+            KeyUsages::DigitalSignature | KeyUsages::CRLSign
+        );
+        assert!(parse_time("2025-01-01").is_err());
+    }
+}